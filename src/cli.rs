@@ -1,6 +1,7 @@
 //! Command-line interface definition for FastHooks
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
 
 /// FastHooks - Blazing fast Git hooks manager
 ///
@@ -19,8 +20,35 @@ pub struct Cli {
     #[arg(short, long, global = true)]
     pub quiet: bool,
 
+    /// When to use colored output
+    #[arg(long, value_enum, global = true, default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
+
+    /// Subcommand to run; shows a status overview when omitted
     #[command(subcommand)]
-    pub command: Commands,
+    pub command: Option<Commands>,
+}
+
+/// Values for `--color`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Always emit colors, even when not writing to a terminal
+    Always,
+    /// Never emit colors
+    Never,
+    /// Color only when writing to a terminal (respects `NO_COLOR`)
+    Auto,
+}
+
+impl ColorChoice {
+    /// Apply the choice to all colored output of this process
+    pub fn apply(self) {
+        match self {
+            ColorChoice::Always => colored::control::set_override(true),
+            ColorChoice::Never => colored::control::set_override(false),
+            ColorChoice::Auto => {}
+        }
+    }
 }
 
 #[derive(Subcommand, Debug)]
@@ -38,25 +66,26 @@ pub enum Commands {
         /// If not specified, installs all configured hooks
         #[arg(short = 'H', long)]
         hook: Option<String>,
+
+        /// Also uninstall FastHooks hooks that are no longer configured
+        #[arg(long, conflicts_with = "hook")]
+        sync: bool,
     },
 
     /// Uninstall all FastHooks Git hooks
-    Uninstall,
-
-    /// Manually run a hook
-    Run {
-        /// Hook name to run (e.g., pre-commit)
-        hook: String,
-
-        /// Specific files to run the hook on
-        #[arg(short, long)]
-        files: Option<Vec<String>>,
+    Uninstall {
+        /// Also delete fasthooks.toml and FastHooks state in .git/fasthooks
+        #[arg(long)]
+        purge: bool,
 
-        /// Hook arguments passed by Git (e.g., commit message file for commit-msg hook)
-        #[arg(trailing_var_arg = true)]
-        args: Vec<String>,
+        /// Skip the confirmation prompt for --purge
+        #[arg(short, long, requires = "purge")]
+        yes: bool,
     },
 
+    /// Manually run a hook
+    Run(Box<RunArgs>),
+
     /// Add a command to a hook
     Add {
         /// Hook name (e.g., pre-commit)
@@ -67,14 +96,238 @@ pub enum Commands {
     },
 
     /// List all configured hooks
-    List,
+    List {
+        /// Show tasks indented under their dependencies, in execution order
+        #[arg(long)]
+        tree: bool,
+
+        /// Show each task's median duration over recent runs
+        #[arg(long)]
+        timings: bool,
+    },
 
     /// Validate the configuration file
-    Validate,
+    Validate {
+        /// Explain why each task would or wouldn't run in the current repository state
+        #[arg(long)]
+        explain: bool,
+
+        /// Warn when `npm run <script>` / `cargo <alias>` commands reference missing scripts
+        #[arg(long)]
+        check_scripts: bool,
+    },
+
+    /// Read or modify individual configuration values
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommands,
+    },
 
     /// Migrate from Husky to FastHooks
     Migrate,
 
     /// Run performance benchmark comparing FastHooks vs Husky
-    Benchmark,
+    Benchmark {
+        /// Measured runs per command (after one discarded warmup run)
+        #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u32).range(1..))]
+        iterations: u32,
+    },
+
+    /// Show the repository, configuration and installed hooks (the default)
+    Status,
+}
+
+/// Subcommands of `fasthooks config`
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommands {
+    /// Print a configuration value (e.g., settings.parallel)
+    Get {
+        /// Dotted path to the value
+        path: String,
+    },
+
+    /// Set a configuration value (e.g., settings.parallel false)
+    Set {
+        /// Dotted path to the value
+        path: String,
+
+        /// New value (bool, integer or string)
+        value: String,
+    },
+
+    /// Check the configuration against best practices (advisory, never fails)
+    Lint,
+}
+
+/// Arguments for the `run` command
+#[derive(Args, Debug)]
+pub struct RunArgs {
+    /// Hook name to run (e.g., pre-commit)
+    pub hook: String,
+
+    /// Specific files to run the hook on (glob patterns are expanded)
+    #[arg(short, long)]
+    pub files: Option<Vec<String>>,
+
+    /// Run without a git repository (globbed tasks need --files)
+    #[arg(long, conflicts_with_all = ["since_last_run", "select_failed"])]
+    pub no_git: bool,
+
+    /// Run tasks in a temporary checkout of the index so unstaged changes can't affect them
+    #[arg(long, conflicts_with_all = ["files", "no_git"])]
+    pub isolated: bool,
+
+    /// Override the maximum number of parallel tasks (0 = auto-detect)
+    #[arg(long, value_name = "N")]
+    pub max_parallel: Option<usize>,
+
+    /// Stop after N task failures instead of at the first one, even in hooks that set
+    /// `fail_fast`
+    #[arg(long, value_name = "N")]
+    pub max_failures: Option<usize>,
+
+    /// Only run the named task(s) and their dependencies
+    #[arg(long, value_name = "TASK")]
+    pub only: Vec<String>,
+
+    /// Ignore task dependencies and run the selected tasks standalone
+    #[arg(long)]
+    pub no_deps: bool,
+
+    /// Run even as root when `settings.forbid_root` is set
+    #[arg(long)]
+    pub allow_root: bool,
+
+    /// Run tasks one at a time in dependency order and stop at the first failure
+    #[arg(long, conflicts_with = "max_failures")]
+    pub bisect: bool,
+
+    /// Only run the tasks that failed in this hook's last run (plus their dependencies)
+    #[arg(long, conflicts_with = "only")]
+    pub select_failed: bool,
+
+    /// Only consider files modified since this hook last ran successfully
+    #[arg(long)]
+    pub since_last_run: bool,
+
+    /// Print the files each task's glob matches and exit without running anything
+    #[arg(long)]
+    pub list_files: bool,
+
+    /// Fail when no task ran because all were skipped or filtered out
+    #[arg(long)]
+    pub require_tasks: bool,
+
+    /// Show the inputs and formula behind the carbon savings estimate
+    #[arg(long)]
+    pub carbon_detail: bool,
+
+    /// Randomize the order of tasks without dependencies (optionally with a fixed seed)
+    #[arg(long, value_name = "SEED", num_args = 0..=1)]
+    pub shuffle: Option<Option<u64>>,
+
+    /// Write a JUnit XML report with one testcase per task
+    #[arg(long, value_name = "PATH")]
+    pub junit: Option<PathBuf>,
+
+    /// Emit GitHub Actions `::error` annotations for failed tasks (on by default in Actions)
+    #[arg(long)]
+    pub github_annotations: bool,
+
+    /// Write the run's result (tasks, skipped tasks and stats) as JSON to PATH
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Write the environment each task runs with to DIR/<task>.env, for reproducing failures
+    #[arg(long, value_name = "DIR")]
+    pub dump_env: Option<PathBuf>,
+
+    /// Write Prometheus textfile metrics for this run to PATH (other hooks' samples are kept)
+    #[arg(long, value_name = "PATH")]
+    pub metrics_file: Option<PathBuf>,
+
+    /// Hook arguments passed by Git (e.g., commit message file for commit-msg hook)
+    pub args: Vec<String>,
+
+    /// Extra arguments after `--`, appended to every task's command
+    #[arg(last = true, value_name = "TASK_ARGS")]
+    pub passthrough: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use colored::Colorize;
+
+    /// Undoes the process-wide color override when dropped, also if the test panics
+    struct ColorOverrideGuard;
+
+    impl Drop for ColorOverrideGuard {
+        fn drop(&mut self) {
+            colored::control::unset_override();
+        }
+    }
+
+    #[test]
+    fn test_color_never_strips_escape_codes() {
+        let cli = Cli::parse_from(["fasthooks", "--color", "never", "list"]);
+        assert_eq!(cli.color, ColorChoice::Never);
+
+        let _guard = ColorOverrideGuard;
+        cli.color.apply();
+        let output = format!("{} {}", "✓".green().bold(), "lint".cyan());
+        assert!(!output.contains('\x1b'));
+        assert_eq!(output, "✓ lint");
+    }
+
+    #[test]
+    fn test_run_separates_hook_args_from_passthrough() {
+        let parse = |args: &[&str]| match Cli::parse_from(args).command {
+            Some(Commands::Run(run)) => (run.args, run.passthrough),
+            other => panic!("expected run, got {:?}", other),
+        };
+
+        assert_eq!(
+            parse(&["fasthooks", "run", "pre-push", "origin", "git@host:repo"]),
+            (
+                vec!["origin".to_string(), "git@host:repo".to_string()],
+                vec![]
+            )
+        );
+        assert_eq!(
+            parse(&["fasthooks", "run", "pre-commit", "--", "--verbose"]),
+            (vec![], vec!["--verbose".to_string()])
+        );
+        assert_eq!(
+            parse(&["fasthooks", "run", "commit-msg", "msg.txt", "--", "-x", "y"]),
+            (
+                vec!["msg.txt".to_string()],
+                vec!["-x".to_string(), "y".to_string()]
+            )
+        );
+    }
+
+    #[test]
+    fn test_color_flag_is_global() {
+        let cli = Cli::parse_from(["fasthooks", "run", "pre-commit", "--color", "always"]);
+        assert_eq!(cli.color, ColorChoice::Always);
+        assert_eq!(
+            Cli::parse_from(["fasthooks", "list"]).color,
+            ColorChoice::Auto
+        );
+    }
+
+    #[test]
+    fn test_bisect_conflicts_with_max_failures() {
+        let err = Cli::try_parse_from([
+            "fasthooks",
+            "run",
+            "pre-commit",
+            "--bisect",
+            "--max-failures",
+            "0",
+        ])
+        .unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
 }
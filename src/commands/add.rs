@@ -1,9 +1,10 @@
 //! Add a command to a hook
 
 use crate::config::{self, Config, ConfigParser, Hook, HookType, Task, CONFIG_FILE_NAME};
-use anyhow::{Context, Result};
+use crate::utils::{modified_time, write_if_unchanged};
+use anyhow::Result;
 use colored::Colorize;
-use std::fs;
+use std::path::PathBuf;
 
 /// Add a command to a hook
 pub fn run(hook_name: String, command: String) -> Result<()> {
@@ -15,6 +16,16 @@ pub fn run(hook_name: String, command: String) -> Result<()> {
         ));
     }
 
+    // Remember when the config was read so concurrent edits aren't clobbered
+    let config_path = config::find_config_file().unwrap_or_else(|| PathBuf::from(CONFIG_FILE_NAME));
+    if ConfigParser::is_yaml(&config_path) {
+        anyhow::bail!(
+            "{} is a YAML config; add the task by hand (only TOML configs are rewritten)",
+            config_path.display()
+        );
+    }
+    let read_at = modified_time(&config_path);
+
     // Load or create config
     let mut config = match config::load_config() {
         Ok(c) => c,
@@ -43,21 +54,15 @@ pub fn run(hook_name: String, command: String) -> Result<()> {
 
     let task = Task {
         name: task_name.clone(),
-        run: command.clone(),
-        glob: None,
-        staged: true,
-        cwd: None,
-        env: Default::default(),
-        allow_failure: false,
-        condition: None,
-        depends_on: Vec::new(),
+        run: command.clone().into(),
+        ..Default::default()
     };
 
     hook.tasks.push(task);
 
     // Save config
     let config_content = ConfigParser::to_toml(&config)?;
-    fs::write(CONFIG_FILE_NAME, config_content).context("Failed to write configuration file")?;
+    write_if_unchanged(&config_path, config_content, read_at)?;
 
     println!(
         "{} Added task '{}' to {} hook",
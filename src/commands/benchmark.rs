@@ -5,17 +5,110 @@ use colored::Colorize;
 use std::process::Command;
 use std::time::Instant;
 
-/// Run performance benchmark
-pub fn run() -> Result<()> {
+/// Other Git hook managers to compare against: (display name, program, version args)
+const COMPETITORS: &[(&str, &str, &[&str])] = &[
+    ("Lefthook", "lefthook", &["version"]),
+    ("pre-commit", "pre-commit", &["--version"]),
+];
+
+/// Summary statistics over repeated timings, in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Measurement {
+    mean_ms: f64,
+    median_ms: f64,
+    stddev_ms: f64,
+    min_ms: f64,
+}
+
+impl Measurement {
+    /// Compute the statistics of a set of samples (sample standard deviation)
+    fn from_samples(samples: &[f64]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let n = sorted.len();
+        let mean_ms = sorted.iter().sum::<f64>() / n as f64;
+        let median_ms = if n % 2 == 0 {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
+        } else {
+            sorted[n / 2]
+        };
+        let stddev_ms = if n > 1 {
+            let variance =
+                sorted.iter().map(|x| (x - mean_ms).powi(2)).sum::<f64>() / (n - 1) as f64;
+            variance.sqrt()
+        } else {
+            0.0
+        };
+
+        Some(Self {
+            mean_ms,
+            median_ms,
+            stddev_ms,
+            min_ms: sorted[0],
+        })
+    }
+
+    /// Median rounded to whole milliseconds, used for comparisons
+    fn median(&self) -> u64 {
+        self.median_ms.round() as u64
+    }
+
+    /// Format the statistics, e.g. `mean 12.4ms ± 0.8ms, min 11.2ms`
+    fn details(&self) -> String {
+        format!(
+            "mean {:.1}ms ± {:.1}ms, min {:.1}ms",
+            self.mean_ms, self.stddev_ms, self.min_ms
+        )
+    }
+}
+
+/// Startup time of an installed competitor
+#[derive(Debug, Clone, PartialEq)]
+struct CompetitorResult {
+    name: &'static str,
+    startup_ms: u64,
+}
+
+/// Benchmark every installed competitor, skipping tools that aren't available
+fn benchmark_competitors(
+    is_installed: impl Fn(&str) -> bool,
+    measure: impl Fn(&str, &[&str]) -> Option<u64>,
+) -> Vec<CompetitorResult> {
+    COMPETITORS
+        .iter()
+        .filter(|(_, program, _)| is_installed(program))
+        .filter_map(|(name, program, args)| {
+            measure(program, args).map(|startup_ms| CompetitorResult { name, startup_ms })
+        })
+        .collect()
+}
+
+/// Run performance benchmark with `iterations` measured runs per command
+pub fn run(iterations: u32) -> Result<()> {
     println!("{}", "FastHooks Performance Benchmark".bold());
     println!("{}", "═".repeat(50));
     println!();
+    println!(
+        "  {}",
+        format!(
+            "Median of {} runs per command, after one warmup run",
+            iterations
+        )
+        .dimmed()
+    );
+    println!();
 
     // Benchmark FastHooks startup time
-    let fasthooks_startup = benchmark_startup("fasthooks", &["--version"]);
+    let fasthooks_measurement = benchmark_command("fasthooks", &["--version"], iterations);
+    let fasthooks_startup = fasthooks_measurement.map(|m| m.median());
 
     // Try to benchmark Husky/npx startup for comparison
-    let husky_startup = benchmark_startup("npx", &["husky", "--version"]);
+    let husky_measurement = benchmark_command("npx", &["husky", "--version"], iterations);
+    let husky_startup = husky_measurement.map(|m| m.median());
 
     // Display results
     println!("{}", "Startup Time Comparison".cyan().bold());
@@ -24,14 +117,14 @@ pub fn run() -> Result<()> {
     println!(
         "  {} FastHooks: {}",
         "⚡".yellow(),
-        format_duration(fasthooks_startup)
+        format_measurement(fasthooks_measurement)
     );
 
     if let Some(husky_time) = husky_startup {
         println!(
             "  {} Husky (npx): {}",
             "🐢".dimmed(),
-            format_duration(Some(husky_time))
+            format_measurement(husky_measurement)
         );
 
         if let Some(fast_time) = fasthooks_startup {
@@ -78,35 +171,59 @@ pub fn run() -> Result<()> {
         println!("    Install Husky to compare: npm install -D husky");
     }
 
+    // Other hook managers, when installed
+    let competitors = benchmark_competitors(
+        |program| which::which(program).is_ok(),
+        |program, args| benchmark_command(program, args, iterations).map(|m| m.median()),
+    );
+    if !competitors.is_empty() {
+        println!();
+        println!("{}", "Other Hook Managers".cyan().bold());
+        println!();
+        for competitor in &competitors {
+            let speedup = fasthooks_startup
+                .and_then(|fast| relative_speed(competitor.startup_ms, fast))
+                .map(|speed| format!(" ({})", speed))
+                .unwrap_or_default();
+            println!(
+                "  {} {}: {}{}",
+                "→".dimmed(),
+                competitor.name,
+                format_duration(Some(competitor.startup_ms)),
+                speedup.dimmed()
+            );
+        }
+    }
+
     println!();
     println!("{}", "Hook Execution Benchmark".cyan().bold());
     println!();
 
     // Benchmark a simple command
-    let echo_time = benchmark_command("echo", &["hello"]);
+    let echo_time = benchmark_command("echo", &["hello"], iterations);
     println!(
         "  {} Simple command (echo): {}",
         "→".dimmed(),
-        format_duration(echo_time)
+        format_measurement(echo_time)
     );
 
     // Benchmark npm if available
-    let npm_time = benchmark_command("npm", &["--version"]);
+    let npm_time = benchmark_command("npm", &["--version"], iterations);
     if npm_time.is_some() {
         println!(
             "  {} npm --version: {}",
             "→".dimmed(),
-            format_duration(npm_time)
+            format_measurement(npm_time)
         );
     }
 
     // Benchmark node if available
-    let node_time = benchmark_command("node", &["--version"]);
+    let node_time = benchmark_command("node", &["--version"], iterations);
     if node_time.is_some() {
         println!(
             "  {} node --version: {}",
             "→".dimmed(),
-            format_duration(node_time)
+            format_measurement(node_time)
         );
     }
 
@@ -123,30 +240,31 @@ pub fn run() -> Result<()> {
     Ok(())
 }
 
-/// Benchmark the startup time of a command
-fn benchmark_startup(cmd: &str, args: &[&str]) -> Option<u64> {
-    benchmark_command(cmd, args)
-}
-
-/// Benchmark a command execution
-fn benchmark_command(cmd: &str, args: &[&str]) -> Option<u64> {
-    let iterations = 5;
-    let mut times = Vec::with_capacity(iterations);
+/// Time a command over `iterations` runs after a discarded warmup run (cold caches)
+fn benchmark_command(cmd: &str, args: &[&str], iterations: u32) -> Option<Measurement> {
+    Command::new(cmd).args(args).output().ok()?;
 
+    let mut samples = Vec::with_capacity(iterations as usize);
     for _ in 0..iterations {
         let start = Instant::now();
-        let result = Command::new(cmd).args(args).output();
+        Command::new(cmd).args(args).output().ok()?;
+        samples.push(start.elapsed().as_secs_f64() * 1000.0);
+    }
 
-        if result.is_err() {
-            return None;
-        }
+    Measurement::from_samples(&samples)
+}
 
-        times.push(start.elapsed().as_millis() as u64);
+/// How a competitor's startup compares to FastHooks', e.g. "3.2x slower" or "1.5x faster"
+fn relative_speed(competitor_ms: u64, fasthooks_ms: u64) -> Option<String> {
+    if competitor_ms == 0 || fasthooks_ms == 0 {
+        return None;
     }
-
-    // Return median
-    times.sort();
-    Some(times[iterations / 2])
+    let (ratio, comparison) = if competitor_ms >= fasthooks_ms {
+        (competitor_ms as f64 / fasthooks_ms as f64, "slower")
+    } else {
+        (fasthooks_ms as f64 / competitor_ms as f64, "faster")
+    };
+    Some(format!("{:.1}x {}", ratio, comparison))
 }
 
 /// Format duration for display
@@ -157,3 +275,67 @@ fn format_duration(ms: Option<u64>) -> String {
         None => "N/A".dimmed().to_string(),
     }
 }
+
+/// Format a measurement's median for display, followed by its statistics
+fn format_measurement(measurement: Option<Measurement>) -> String {
+    match measurement {
+        Some(m) => format!(
+            "{} {}",
+            format_duration(Some(m.median())),
+            format!("({})", m.details()).dimmed()
+        ),
+        None => format_duration(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_benchmark_competitors_only_installed() {
+        let results = benchmark_competitors(|program| program == "pre-commit", |_, _| Some(180));
+        assert_eq!(
+            results,
+            vec![CompetitorResult {
+                name: "pre-commit",
+                startup_ms: 180,
+            }]
+        );
+
+        // Installed but failing to run is skipped as well
+        let results =
+            benchmark_competitors(|_| true, |program, _| (program == "lefthook").then_some(12));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "Lefthook");
+
+        assert!(benchmark_competitors(|_| false, |_, _| Some(1)).is_empty());
+    }
+
+    #[test]
+    fn test_relative_speed() {
+        assert_eq!(relative_speed(48, 12).as_deref(), Some("4.0x slower"));
+        assert_eq!(relative_speed(5, 12).as_deref(), Some("2.4x faster"));
+        assert_eq!(relative_speed(12, 0), None);
+    }
+
+    #[test]
+    fn test_measurement_from_samples() {
+        let m = Measurement::from_samples(&[12.0, 10.0, 14.0, 10.0, 14.0]).unwrap();
+        assert_eq!(m.mean_ms, 12.0);
+        assert_eq!(m.median_ms, 12.0);
+        assert_eq!(m.min_ms, 10.0);
+        // Sample variance: (0 + 4 + 4 + 4 + 4) / 4 = 4
+        assert_eq!(m.stddev_ms, 2.0);
+        assert_eq!(m.median(), 12);
+
+        let m = Measurement::from_samples(&[3.0, 1.0, 2.0, 10.0]).unwrap();
+        assert_eq!(m.median_ms, 2.5);
+        assert_eq!(m.mean_ms, 4.0);
+
+        let m = Measurement::from_samples(&[7.5]).unwrap();
+        assert_eq!((m.median_ms, m.stddev_ms), (7.5, 0.0));
+
+        assert!(Measurement::from_samples(&[]).is_none());
+    }
+}
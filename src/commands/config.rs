@@ -0,0 +1,240 @@
+//! Read and modify individual configuration values
+
+use crate::cli::ConfigCommands;
+use crate::config::{self, lint, Config, ConfigParser, Hook, Task};
+use crate::utils::{modified_time, write_if_unchanged};
+use anyhow::{Context, Result};
+use colored::Colorize;
+use toml::Value;
+
+/// Run the config command
+pub fn run(action: ConfigCommands) -> Result<()> {
+    let config_path = config::find_config_file()
+        .context("No fasthooks.toml found. Run 'fasthooks init' to create one.")?;
+    let read_at = modified_time(&config_path);
+    let config = ConfigParser::parse_file(&config_path)?;
+
+    match action {
+        ConfigCommands::Get { path } => match get_value(&config, &path)? {
+            Value::String(s) => println!("{}", s),
+            Value::Table(table) => print!("{}", toml::to_string_pretty(&table)?),
+            value => println!("{}", value),
+        },
+        ConfigCommands::Set { path, value } => {
+            if ConfigParser::is_yaml(&config_path) {
+                anyhow::bail!(
+                    "{} is a YAML config; edit it by hand (only TOML configs are rewritten)",
+                    config_path.display()
+                );
+            }
+            let updated = set_value(&config, &path, &value)?;
+            let config_content = ConfigParser::to_toml(&updated)?;
+            write_if_unchanged(&config_path, config_content, read_at)?;
+
+            println!(
+                "{} Set {} = {}",
+                "✓".green().bold(),
+                path.cyan(),
+                get_value(&updated, &path)?
+            );
+        }
+        ConfigCommands::Lint => {
+            let findings = lint::lint(&config);
+            if findings.is_empty() {
+                println!("{} No lint findings", "✓".green().bold());
+            } else {
+                print!("{}", lint::format_lint(&findings));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Look up a configuration value by dotted path (e.g., `hooks.pre-commit.tasks.0.run`)
+pub fn get_value(config: &Config, path: &str) -> Result<Value> {
+    let root = to_value(config)?;
+
+    let mut current = &root;
+    for segment in path.split('.') {
+        current = child(current, segment)
+            .with_context(|| format!("Unknown configuration key: {}", path))?;
+    }
+
+    Ok(current.clone())
+}
+
+/// Return a copy of the configuration with the value at `path` replaced
+pub fn set_value(config: &Config, path: &str, raw: &str) -> Result<Config> {
+    let mut root = to_value(config)?;
+    let (parent_path, key) = path.rsplit_once('.').unwrap_or(("", path));
+
+    let mut parent = &mut root;
+    for segment in parent_path.split('.').filter(|s| !s.is_empty()) {
+        parent = child_mut(parent, segment)
+            .with_context(|| format!("Unknown configuration key: {}", path))?;
+    }
+
+    match parent {
+        Value::Table(table) => {
+            let value = parse_value(raw, table.get(key))?;
+            table.insert(key.to_string(), value);
+        }
+        Value::Array(array) => {
+            let slot = key
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| array.get_mut(i))
+                .with_context(|| format!("Unknown configuration key: {}", path))?;
+            *slot = parse_value(raw, Some(slot))?;
+        }
+        _ => anyhow::bail!("Unknown configuration key: {}", path),
+    }
+
+    let updated: Config = root
+        .try_into()
+        .with_context(|| format!("Invalid value for {}: {}", path, raw))?;
+
+    // Keys outside the schema are silently dropped on deserialization
+    if get_value(&updated, path).is_err() {
+        anyhow::bail!("Unknown configuration key: {}", path);
+    }
+
+    Ok(updated)
+}
+
+/// Serialize the configuration with every schema key, adding the default-valued
+/// fields that serialization leaves out
+fn to_value(config: &Config) -> Result<Value> {
+    let mut root = Value::try_from(config).context("Failed to serialize configuration")?;
+    add_defaults(&mut root, Config::omitted_defaults());
+
+    if let Some(Value::Table(hooks)) = root.get_mut("hooks") {
+        for (_, hook) in hooks.iter_mut() {
+            add_defaults(hook, Hook::omitted_defaults());
+            if let Some(Value::Array(tasks)) = hook.get_mut("tasks") {
+                for task in tasks {
+                    add_defaults(task, Task::omitted_defaults());
+                }
+            }
+        }
+    }
+
+    Ok(root)
+}
+
+/// Insert the `defaults` missing from a table
+fn add_defaults(value: &mut Value, defaults: toml::Table) {
+    if let Value::Table(table) = value {
+        for (key, default) in defaults {
+            table.entry(key).or_insert(default);
+        }
+    }
+}
+
+/// Get a child of a table (by key) or array (by index)
+fn child<'a>(value: &'a Value, segment: &str) -> Option<&'a Value> {
+    match value {
+        Value::Table(table) => table.get(segment),
+        Value::Array(array) => segment.parse::<usize>().ok().and_then(|i| array.get(i)),
+        _ => None,
+    }
+}
+
+/// Mutable variant of [`child`]
+fn child_mut<'a>(value: &'a mut Value, segment: &str) -> Option<&'a mut Value> {
+    match value {
+        Value::Table(table) => table.get_mut(segment),
+        Value::Array(array) => segment.parse::<usize>().ok().and_then(|i| array.get_mut(i)),
+        _ => None,
+    }
+}
+
+/// Parse a raw command-line value, keeping the type of the existing value if any
+fn parse_value(raw: &str, existing: Option<&Value>) -> Result<Value> {
+    match existing {
+        Some(Value::String(_)) => Ok(Value::String(raw.to_string())),
+        Some(Value::Boolean(_)) => raw
+            .parse::<bool>()
+            .map(Value::Boolean)
+            .with_context(|| format!("Expected true or false, got '{}'", raw)),
+        Some(Value::Integer(_)) => raw
+            .parse::<i64>()
+            .map(Value::Integer)
+            .with_context(|| format!("Expected an integer, got '{}'", raw)),
+        Some(Value::Table(_)) | Some(Value::Array(_)) => {
+            anyhow::bail!("Only bool, integer and string values can be set")
+        }
+        _ => Ok(if let Ok(b) = raw.parse::<bool>() {
+            Value::Boolean(b)
+        } else if let Ok(i) = raw.parse::<i64>() {
+            Value::Integer(i)
+        } else {
+            Value::String(raw.to_string())
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> Config {
+        ConfigParser::parse_toml(&ConfigParser::default_config_content()).unwrap()
+    }
+
+    #[test]
+    fn test_set_and_get_bool() {
+        let config = test_config();
+        assert_eq!(
+            get_value(&config, "settings.parallel").unwrap(),
+            Value::Boolean(true)
+        );
+
+        let updated = set_value(&config, "settings.parallel", "false").unwrap();
+        assert!(!updated.settings.parallel);
+        assert_eq!(
+            get_value(&updated, "settings.parallel").unwrap(),
+            Value::Boolean(false)
+        );
+    }
+
+    #[test]
+    fn test_set_int_and_string() {
+        let config = test_config();
+
+        let updated = set_value(&config, "settings.max_parallel", "4").unwrap();
+        assert_eq!(updated.settings.max_parallel, 4);
+
+        let updated = set_value(&updated, "hooks.pre-commit.tasks.0.run", "eslint .").unwrap();
+        assert_eq!(
+            updated.hooks["pre-commit"].tasks[0].run.script(),
+            "eslint ."
+        );
+
+        // Strings keep their type even when they look like numbers
+        let updated = set_value(&updated, "version", "2").unwrap();
+        assert_eq!(updated.version, "2");
+    }
+
+    #[test]
+    fn test_set_rejects_invalid_values() {
+        let config = test_config();
+        assert!(set_value(&config, "settings.parallel", "maybe").is_err());
+        assert!(set_value(&config, "settings.max_parallel", "-1").is_err());
+        assert!(set_value(&config, "settings.unknown_key", "true").is_err());
+        assert!(get_value(&config, "settings.unknown_key").is_err());
+    }
+
+    #[test]
+    fn test_get_and_set_omitted_defaults() {
+        let config = test_config();
+        let path = "hooks.pre-commit.tasks.0.allow_failure";
+        assert_eq!(get_value(&config, path).unwrap(), Value::Boolean(false));
+
+        let updated = set_value(&config, path, "true").unwrap();
+        assert!(updated.hooks["pre-commit"].tasks[0].allow_failure);
+        let updated = set_value(&updated, path, "false").unwrap();
+        assert!(!updated.hooks["pre-commit"].tasks[0].allow_failure);
+    }
+}
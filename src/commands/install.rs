@@ -1,26 +1,26 @@
 //! Install Git hooks
 
-use crate::config::{self, HookType};
+use crate::config::{self, Config, HookType};
 use crate::hooks::HookInstaller;
 use anyhow::Result;
 use colored::Colorize;
 
 /// Run the install command
-pub fn run(hook: Option<String>) -> Result<()> {
-    let config = config::load_config()?;
+pub fn run(hook: Option<String>, sync: bool) -> Result<()> {
+    let config = config::load_config_with_scripts()?;
     let installer = HookInstaller::new()?;
 
     match hook {
-        Some(hook_name) => {
+        Some(name) => {
             // Install specific hook
-            let hook_type = HookType::from_str(&hook_name).ok_or_else(|| {
+            let (hook_type, hook_name) = resolve_hook(&config, &name).ok_or_else(|| {
                 anyhow::anyhow!(
                     "Unknown hook type: {}. Valid hooks: pre-commit, pre-push, commit-msg, etc.",
-                    hook_name
+                    name
                 )
             })?;
 
-            if !config.hooks.contains_key(&hook_name) {
+            if !config.hooks.contains_key(hook_name) && !auto_installs(&config, hook_type) {
                 eprintln!(
                     "{} Hook '{}' is not configured in fasthooks.toml",
                     "Warning:".yellow().bold(),
@@ -28,20 +28,41 @@ pub fn run(hook: Option<String>) -> Result<()> {
                 );
             }
 
-            installer.install_hook(hook_type)?;
-            println!("{} Installed {} hook", "✓".green().bold(), hook_name.cyan());
+            install_one(&installer, &config, hook_type, hook_name)?;
+            print_installed(hook_type, hook_name);
         }
         None => {
             // Install all configured hooks
-            let mut installed = 0;
-            for hook_name in config.hooks.keys() {
-                if let Some(hook_type) = HookType::from_str(hook_name) {
-                    installer.install_hook(hook_type)?;
-                    println!("{} Installed {} hook", "✓".green().bold(), hook_name.cyan());
-                    installed += 1;
+            let mut installed = Vec::new();
+            for (hook_type, hook_name) in config.git_hooks() {
+                install_one(&installer, &config, hook_type, hook_name)?;
+                print_installed(hook_type, hook_name);
+                installed.push(hook_type);
+            }
+
+            // The auto-install hook is needed even without post-checkout tasks
+            if needs_auto_install_hook(&config) {
+                install_one(&installer, &config, HookType::PostCheckout, "post-checkout")?;
+                println!(
+                    "{} Installed {} hook (auto-install)",
+                    "✓".green().bold(),
+                    "post-checkout".cyan()
+                );
+                installed.push(HookType::PostCheckout);
+            }
+
+            if sync {
+                for hook_type in installer.remove_stale(&installed)? {
+                    println!(
+                        "{} Removed stale {} hook",
+                        "✓".green().bold(),
+                        hook_type.as_str().cyan()
+                    );
                 }
             }
 
+            let installed = installed.len();
+
             if installed == 0 {
                 println!(
                     "{} No hooks configured in fasthooks.toml",
@@ -56,3 +77,154 @@ pub fn run(hook: Option<String>) -> Result<()> {
 
     Ok(())
 }
+
+/// Check if a hook type is managed by the auto-install post-checkout hook
+fn auto_installs(config: &Config, hook_type: HookType) -> bool {
+    config.settings.auto_install && hook_type == HookType::PostCheckout
+}
+
+/// Check if auto-install needs its own post-checkout hook because no configured
+/// hook installs as post-checkout
+fn needs_auto_install_hook(config: &Config) -> bool {
+    config.settings.auto_install
+        && !config
+            .git_hooks()
+            .iter()
+            .any(|(hook_type, _)| *hook_type == HookType::PostCheckout)
+}
+
+/// Resolve `--hook <name>`, either a configured hook or a Git hook, to the Git hook
+/// to install and the configured hook it runs
+fn resolve_hook<'a>(config: &'a Config, name: &'a str) -> Option<(HookType, &'a str)> {
+    if let Some(hook) = config.hooks.get(name) {
+        if let Some(hook_type) = HookType::from_str(hook.git_hook_name(name)) {
+            return Some((hook_type, name));
+        }
+    }
+
+    let hook_type = HookType::from_str(name)?;
+    let hook_name = config
+        .git_hooks()
+        .into_iter()
+        .find(|(t, _)| *t == hook_type)
+        .map_or(name, |(_, hook_name)| hook_name);
+    Some((hook_type, hook_name))
+}
+
+/// Report an installed hook, naming the configured hook when it differs
+fn print_installed(hook_type: HookType, hook_name: &str) {
+    if hook_name == hook_type.as_str() {
+        println!("{} Installed {} hook", "✓".green().bold(), hook_name.cyan());
+    } else {
+        println!(
+            "{} Installed {} hook as {}",
+            "✓".green().bold(),
+            hook_name.cyan(),
+            hook_type.as_str().cyan()
+        );
+    }
+}
+
+/// Install a single Git hook running the configured hook `hook_name`, using the
+/// auto-install template for post-checkout when enabled
+fn install_one(
+    installer: &HookInstaller,
+    config: &Config,
+    hook_type: HookType,
+    hook_name: &str,
+) -> Result<()> {
+    if auto_installs(config, hook_type) {
+        installer.install_auto_install(config.hooks.contains_key(hook_name).then_some(hook_name))
+    } else {
+        installer.install_hook_as(hook_type, hook_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigParser;
+    use crate::hooks::GitRepository;
+    use git2::Repository;
+    use tempfile::TempDir;
+
+    const CI_CONFIG: &str = r#"
+[hooks.ci]
+installs_as = "pre-push"
+[[hooks.ci.tasks]]
+name = "test"
+run = "cargo test"
+
+[hooks.pre-commit]
+[[hooks.pre-commit.tasks]]
+name = "fmt"
+run = "cargo fmt --check"
+"#;
+
+    #[test]
+    fn test_resolve_custom_named_hook() {
+        let config = ConfigParser::parse_toml(CI_CONFIG).unwrap();
+
+        assert_eq!(resolve_hook(&config, "ci"), Some((HookType::PrePush, "ci")));
+        assert_eq!(
+            resolve_hook(&config, "pre-push"),
+            Some((HookType::PrePush, "ci"))
+        );
+        assert_eq!(
+            resolve_hook(&config, "pre-commit"),
+            Some((HookType::PreCommit, "pre-commit"))
+        );
+        assert_eq!(resolve_hook(&config, "deploy"), None);
+    }
+
+    #[test]
+    fn test_install_custom_named_hook_as_pre_push() {
+        let temp_dir = TempDir::new().unwrap();
+        Repository::init(temp_dir.path()).unwrap();
+        let installer = HookInstaller::from_repo(GitRepository::open(temp_dir.path()).unwrap());
+        let config = ConfigParser::parse_toml(CI_CONFIG).unwrap();
+
+        for (hook_type, hook_name) in config.git_hooks() {
+            install_one(&installer, &config, hook_type, hook_name).unwrap();
+        }
+
+        let hooks_dir = temp_dir.path().join(".git/hooks");
+        let pre_push = std::fs::read_to_string(hooks_dir.join("pre-push")).unwrap();
+        assert!(pre_push.contains("fasthooks run ci \"$@\""));
+        assert!(!hooks_dir.join("ci").exists());
+        assert_eq!(
+            installer.installed_hooks(),
+            vec![HookType::PreCommit, HookType::PrePush]
+        );
+    }
+
+    #[test]
+    fn test_auto_install_runs_custom_named_post_checkout_hook() {
+        let temp_dir = TempDir::new().unwrap();
+        Repository::init(temp_dir.path()).unwrap();
+        let installer = HookInstaller::from_repo(GitRepository::open(temp_dir.path()).unwrap());
+        let config = ConfigParser::parse_toml(
+            r#"
+[settings]
+auto_install = true
+
+[hooks.sync]
+installs_as = "post-checkout"
+[[hooks.sync.tasks]]
+name = "deps"
+run = "npm install"
+"#,
+        )
+        .unwrap();
+
+        assert!(!needs_auto_install_hook(&config));
+        for (hook_type, hook_name) in config.git_hooks() {
+            install_one(&installer, &config, hook_type, hook_name).unwrap();
+        }
+
+        let post_checkout =
+            std::fs::read_to_string(temp_dir.path().join(".git/hooks/post-checkout")).unwrap();
+        assert!(post_checkout.contains("fasthooks run sync \"$@\""));
+        assert!(post_checkout.contains("fasthooks install"));
+    }
+}
@@ -1,13 +1,15 @@
 //! List configured hooks
 
-use crate::config;
-use crate::hooks;
+use crate::config::{self, Hook, Task};
+use crate::hooks::{self, GitRepository};
+use crate::runner::{ExecutionStats, TaskExecutor, TaskTimings, Timings};
 use anyhow::Result;
 use colored::Colorize;
+use std::collections::HashMap;
 
 /// List all configured hooks
-pub fn run() -> Result<()> {
-    let config = match config::load_config() {
+pub fn run(tree: bool, timings: bool) -> Result<()> {
+    let config = match config::load_config_with_scripts() {
         Ok(c) => c,
         Err(_) => {
             println!(
@@ -35,17 +37,35 @@ pub fn run() -> Result<()> {
         return Ok(());
     }
 
+    let state_dir = if timings {
+        GitRepository::discover().ok().map(|repo| repo.state_dir())
+    } else {
+        None
+    };
+
     for (hook_name, hook) in &config.hooks {
-        let status = if is_installed {
-            "●".green()
-        } else {
-            "○".yellow()
-        };
+        println!("{}", hook_heading(hook_name, hook, is_installed));
 
-        println!("{} {} hook", status, hook_name.cyan().bold());
+        // Without a repository there is no history, so every task shows "—"
+        let hook_timings = timings.then(|| {
+            state_dir
+                .as_deref()
+                .map(|dir| Timings::load(dir, hook_name))
+                .unwrap_or_default()
+        });
+        let suffix = |task: &Task| {
+            hook_timings
+                .as_ref()
+                .map(|t| format!(" {}", timing_label(t, &task.name).dimmed()))
+                .unwrap_or_default()
+        };
 
         if hook.tasks.is_empty() {
             println!("    (no tasks)");
+        } else if tree {
+            for line in tree_lines(hook, suffix)? {
+                println!("    {}", line);
+            }
         } else {
             for task in &hook.tasks {
                 let glob_info = task
@@ -54,8 +74,16 @@ pub fn run() -> Result<()> {
                     .map(|g| format!(" [{}]", g.dimmed()))
                     .unwrap_or_default();
 
-                println!("    {} {}{}", "→".dimmed(), task.name, glob_info);
-                println!("      {}", task.run.dimmed());
+                println!(
+                    "    {} {}{}{}",
+                    "→".dimmed(),
+                    task.name,
+                    glob_info,
+                    suffix(task)
+                );
+                for command in task.run.commands() {
+                    println!("      {}", command.dimmed());
+                }
             }
         }
         println!();
@@ -71,3 +99,151 @@ pub fn run() -> Result<()> {
 
     Ok(())
 }
+
+/// Median duration of a task over its recorded runs, e.g. "~1.20s", or "—" without history
+fn timing_label(timings: &TaskTimings, task_name: &str) -> String {
+    match Timings::median_ms(timings, task_name) {
+        Some(ms) => format!("~{}", ExecutionStats::format_duration(ms)),
+        None => "—".to_string(),
+    }
+}
+
+/// Render a hook's tasks in execution order, each indented under its deepest dependency
+/// and followed by `suffix`
+fn tree_lines(hook: &Hook, suffix: impl Fn(&Task) -> String) -> Result<Vec<String>> {
+    let sorted = TaskExecutor::sort_tasks_by_dependencies(&hook.tasks)?;
+    let mut depths: HashMap<&str, usize> = HashMap::new();
+    let mut lines = Vec::with_capacity(sorted.len());
+
+    for task in sorted {
+        // Dependencies come first in topological order, so their depth is known
+        let depth = task
+            .depends_on
+            .iter()
+            .filter_map(|dep| depths.get(dep.as_str()))
+            .map(|d| d + 1)
+            .max()
+            .unwrap_or(0);
+        depths.insert(&task.name, depth);
+
+        let line = if depth == 0 {
+            task.name.clone()
+        } else {
+            format!("{}└─ {}", "   ".repeat(depth - 1), task.name)
+        };
+        lines.push(format!("{}{}", line, suffix(task)));
+    }
+
+    Ok(lines)
+}
+
+/// Heading line of a hook: install status, name and description
+fn hook_heading(hook_name: &str, hook: &Hook, is_installed: bool) -> String {
+    let status = if is_installed {
+        "●".green()
+    } else {
+        "○".yellow()
+    };
+    let description = hook
+        .description
+        .as_ref()
+        .map(|d| format!(" — {}", d.dimmed()))
+        .unwrap_or_default();
+
+    format!("{} {} hook{}", status, hook_name.cyan().bold(), description)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigParser;
+    use crate::runner::TaskResult;
+
+    #[test]
+    fn test_tree_lines_indents_dependents() {
+        let content = r#"
+version = "1"
+
+[hooks.pre-push]
+[[hooks.pre-push.tasks]]
+name = "test"
+run = "cargo test"
+depends_on = ["build"]
+
+[[hooks.pre-push.tasks]]
+name = "build"
+run = "cargo build"
+
+[[hooks.pre-push.tasks]]
+name = "package"
+run = "cargo package"
+depends_on = ["test"]
+"#;
+        let config = ConfigParser::parse_toml(content).unwrap();
+
+        let lines = tree_lines(&config.hooks["pre-push"], |_| String::new()).unwrap();
+        assert_eq!(lines, vec!["build", "└─ test", "   └─ package"]);
+    }
+
+    #[test]
+    fn test_timing_labels_from_recorded_runs() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let state_dir = temp_dir.path().join("fasthooks");
+        for duration in [1100, 1300, 1200] {
+            let tasks = [TaskResult::success(
+                "test".to_string(),
+                String::new(),
+                String::new(),
+                duration,
+            )];
+            Timings::record(&state_dir, "pre-push", &tasks).unwrap();
+        }
+        let content = r#"
+[hooks.pre-push]
+[[hooks.pre-push.tasks]]
+name = "build"
+run = "cargo build"
+
+[[hooks.pre-push.tasks]]
+name = "test"
+run = "cargo test"
+depends_on = ["build"]
+"#;
+        let config = ConfigParser::parse_toml(content).unwrap();
+
+        let timings = Timings::load(&state_dir, "pre-push");
+        assert_eq!(timing_label(&timings, "test"), "~1.20s");
+        assert_eq!(timing_label(&timings, "build"), "—");
+
+        let lines = tree_lines(&config.hooks["pre-push"], |task| {
+            format!(" ({})", timing_label(&timings, &task.name))
+        })
+        .unwrap();
+        assert_eq!(lines, vec!["build (—)", "└─ test (~1.20s)"]);
+    }
+
+    #[test]
+    fn test_hook_heading_shows_description() {
+        let content = r#"
+[hooks.pre-commit]
+description = "Formats and lints staged files"
+[[hooks.pre-commit.tasks]]
+name = "fmt"
+run = "cargo fmt"
+
+[hooks.pre-push]
+[[hooks.pre-push.tasks]]
+name = "test"
+run = "cargo test"
+"#;
+        let config = ConfigParser::parse_toml(content).unwrap();
+
+        let heading = hook_heading("pre-commit", &config.hooks["pre-commit"], true);
+        assert!(heading.contains("pre-commit"));
+        assert!(heading.contains(" — "));
+        assert!(heading.contains("Formats and lints staged files"));
+
+        let heading = hook_heading("pre-push", &config.hooks["pre-push"], true);
+        assert!(!heading.contains(" — "));
+    }
+}
@@ -17,16 +17,28 @@ pub fn run() -> Result<()> {
     let husky_dir = Path::new(".husky");
     let has_husky = husky_dir.exists() && husky_dir.is_dir();
 
+    // Husky v4 kept its hooks in package.json instead
+    let husky_v4 = find_husky_v4();
+
     // Check for lint-staged configuration
     let lint_staged_config = find_lint_staged_config();
 
-    if !has_husky && lint_staged_config.is_none() {
+    // Check for simple-git-hooks configuration
+    let simple_git_hooks = find_simple_git_hooks();
+
+    if !has_husky
+        && husky_v4.is_none()
+        && lint_staged_config.is_none()
+        && simple_git_hooks.is_none()
+    {
         println!(
-            "{} No Husky or lint-staged configuration found.",
+            "{} No Husky, simple-git-hooks or lint-staged configuration found.",
             "Warning:".yellow().bold()
         );
         println!("  Looking for:");
         println!("    - .husky/ directory");
+        println!("    - husky hooks in package.json (Husky v4)");
+        println!("    - simple-git-hooks config in package.json");
         println!("    - lint-staged config in package.json");
         println!("    - .lintstagedrc file");
         return Ok(());
@@ -36,6 +48,7 @@ pub fn run() -> Result<()> {
         version: "1".to_string(),
         settings: Settings::default(),
         hooks: HashMap::new(),
+        env_profiles: HashMap::new(),
     };
 
     // Migrate Husky hooks
@@ -44,6 +57,18 @@ pub fn run() -> Result<()> {
         migrate_husky_hooks(husky_dir, &mut config)?;
     }
 
+    // Migrate Husky v4 hooks from package.json
+    if let Some(hooks) = &husky_v4 {
+        println!("{} Found Husky v4 hooks in package.json", "✓".green());
+        migrate_husky_v4(hooks, &mut config)?;
+    }
+
+    // Migrate simple-git-hooks config
+    if let Some(hooks) = &simple_git_hooks {
+        println!("{} Found simple-git-hooks configuration", "✓".green());
+        migrate_simple_git_hooks(hooks, &mut config)?;
+    }
+
     // Migrate lint-staged config
     if let Some(lint_staged) = lint_staged_config {
         println!("{} Found lint-staged configuration", "✓".green());
@@ -59,10 +84,8 @@ pub fn run() -> Result<()> {
 
     // Install hooks
     let installer = HookInstaller::new()?;
-    for hook_name in config.hooks.keys() {
-        if let Some(hook_type) = crate::config::HookType::from_str(hook_name) {
-            installer.install_hook(hook_type)?;
-        }
+    for (hook_type, hook_name) in config.git_hooks() {
+        installer.install_hook_as(hook_type, hook_name)?;
     }
 
     println!();
@@ -108,14 +131,9 @@ fn migrate_husky_hooks(husky_dir: &Path, config: &mut Config) -> Result<()> {
                     for cmd in commands {
                         hook.tasks.push(Task {
                             name: extract_task_name(&cmd),
-                            run: cmd,
-                            glob: None,
-                            staged: true,
-                            cwd: None,
-                            env: HashMap::new(),
-                            allow_failure: false,
-                            condition: None,
-                            depends_on: Vec::new(),
+                            glob: infer_glob(&cmd),
+                            run: cmd.into(),
+                            ..Default::default()
                         });
                     }
 
@@ -144,6 +162,92 @@ fn parse_husky_script(content: &str) -> Vec<String> {
         .collect()
 }
 
+/// Find Husky v4 hooks: `"husky": { "hooks": { ... } }` in `package.json`
+fn find_husky_v4() -> Option<serde_json::Value> {
+    fs::read_to_string("package.json")
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|json| json.get("husky")?.get("hooks").cloned())
+}
+
+/// Migrate Husky v4 hooks: `{ "<hook>": "<command>" }`.
+///
+/// v4 passed Git's arguments in `HUSKY_GIT_PARAMS`; FastHooks substitutes them
+/// as `$1`, `$2`, ... instead.
+fn migrate_husky_v4(hooks: &serde_json::Value, config: &mut Config) -> Result<()> {
+    let Some(hooks) = hooks.as_object() else {
+        return Ok(());
+    };
+
+    for (hook_name, command) in hooks {
+        let Some(command) = command.as_str().map(str::trim).filter(|c| !c.is_empty()) else {
+            continue;
+        };
+        if crate::config::HookType::from_str(hook_name).is_none() {
+            continue;
+        }
+
+        let command = command
+            .replace("-E HUSKY_GIT_PARAMS", "--edit $1")
+            .replace("${HUSKY_GIT_PARAMS}", "$1")
+            .replace("$HUSKY_GIT_PARAMS", "$1");
+        let hook = config.hooks.entry(hook_name.clone()).or_default();
+        hook.tasks.push(Task {
+            name: extract_task_name(&command),
+            glob: infer_glob(&command),
+            run: command.into(),
+            ..Default::default()
+        });
+
+        println!("  {} Migrated {} hook", "→".dimmed(), hook_name.cyan());
+    }
+
+    Ok(())
+}
+
+/// Find simple-git-hooks configuration (`package.json` or `.simple-git-hooks.json`)
+fn find_simple_git_hooks() -> Option<serde_json::Value> {
+    let package = fs::read_to_string("package.json")
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|json| json.get("simple-git-hooks").cloned());
+
+    package.or_else(|| {
+        fs::read_to_string(".simple-git-hooks.json")
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+    })
+}
+
+/// Migrate simple-git-hooks configuration: `{ "<hook>": "<command>" }`
+fn migrate_simple_git_hooks(hooks: &serde_json::Value, config: &mut Config) -> Result<()> {
+    let Some(hooks) = hooks.as_object() else {
+        return Ok(());
+    };
+
+    for (hook_name, command) in hooks {
+        // Skips options like "preserveUnused" and anything that isn't a command
+        let Some(command) = command.as_str().map(str::trim).filter(|c| !c.is_empty()) else {
+            continue;
+        };
+        if crate::config::HookType::from_str(hook_name).is_none() {
+            continue;
+        }
+
+        let hook = config.hooks.entry(hook_name.clone()).or_default();
+        hook.tasks.push(Task {
+            name: extract_task_name(command),
+            glob: infer_glob(command),
+            run: command.into(),
+            ..Default::default()
+        });
+
+        println!("  {} Migrated {} hook", "→".dimmed(), hook_name.cyan());
+    }
+
+    Ok(())
+}
+
 /// Find lint-staged configuration
 fn find_lint_staged_config() -> Option<LintStagedConfig> {
     // Check package.json
@@ -207,14 +311,9 @@ fn migrate_lint_staged(lint_staged: &LintStagedConfig, config: &mut Config) -> R
         for cmd in commands {
             hook.tasks.push(Task {
                 name: extract_task_name(cmd),
-                run: cmd.clone(),
+                run: cmd.clone().into(),
                 glob: Some(pattern.clone()),
-                staged: true,
-                cwd: None,
-                env: HashMap::new(),
-                allow_failure: false,
-                condition: None,
-                depends_on: Vec::new(),
+                ..Default::default()
             });
 
             println!(
@@ -229,7 +328,159 @@ fn migrate_lint_staged(lint_staged: &LintStagedConfig, config: &mut Config) -> R
     Ok(())
 }
 
+/// Known tools and the files they operate on, used to infer task globs.
+/// Entries are checked in order, so more specific tools come first.
+/// Only tools that accept file arguments belong here: a glob makes the task
+/// append the matched files, which breaks project-wide tools like `tsc` or
+/// `cargo clippy`. The matcher has no brace expansion, hence the lists.
+const TOOL_GLOBS: &[(&str, &str)] = &[
+    ("eslint", "*.js, *.ts, *.jsx, *.tsx"),
+    (
+        "prettier",
+        "*.js, *.ts, *.jsx, *.tsx, *.json, *.md, *.css, *.scss, *.yml, *.yaml",
+    ),
+    ("stylelint", "*.css, *.scss, *.less"),
+    ("markdownlint", "*.md"),
+    ("rustfmt", "*.rs"),
+    ("black", "*.py"),
+    ("ruff", "*.py"),
+    ("flake8", "*.py"),
+    ("gofmt", "*.go"),
+];
+
+/// Infer a glob pattern for a migrated command from the tool it invokes
+fn infer_glob(cmd: &str) -> Option<String> {
+    let tokens: Vec<&str> = cmd.split_whitespace().collect();
+
+    TOOL_GLOBS
+        .iter()
+        .find(|(tool, _)| {
+            let tool_tokens: Vec<&str> = tool.split_whitespace().collect();
+            tokens
+                .windows(tool_tokens.len())
+                .any(|window| window == tool_tokens.as_slice())
+        })
+        .map(|(_, glob)| glob.to_string())
+}
+
 /// Extract a task name from a command
 fn extract_task_name(cmd: &str) -> String {
     cmd.split_whitespace().take(2).collect::<Vec<_>>().join(" ")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::TaskExecutor;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_infer_glob_known_tools() {
+        assert_eq!(
+            infer_glob("npx eslint --fix"),
+            Some("*.js, *.ts, *.jsx, *.tsx".to_string())
+        );
+        assert_eq!(infer_glob("rustfmt --check"), Some("*.rs".to_string()));
+        assert_eq!(infer_glob("black ."), Some("*.py".to_string()));
+        assert!(infer_glob("prettier --write").unwrap().contains("json"));
+    }
+
+    #[test]
+    fn test_migrate_husky_v4() {
+        let package: serde_json::Value = serde_json::from_str(
+            r#"{
+                "name": "app",
+                "husky": {
+                    "hooks": {
+                        "pre-commit": "lint-staged",
+                        "commit-msg": "commitlint -E HUSKY_GIT_PARAMS",
+                        "pre-push": "npm test && npm run build",
+                        "not-a-hook": "echo nope"
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+        let mut config = Config::default();
+
+        migrate_husky_v4(&package["husky"]["hooks"], &mut config).unwrap();
+
+        let mut hooks: Vec<&str> = config.hooks.keys().map(String::as_str).collect();
+        hooks.sort();
+        assert_eq!(hooks, vec!["commit-msg", "pre-commit", "pre-push"]);
+        assert_eq!(
+            config.hooks["pre-commit"].tasks[0].run.script(),
+            "lint-staged"
+        );
+        assert_eq!(
+            config.hooks["commit-msg"].tasks[0].run.script(),
+            "commitlint --edit $1"
+        );
+        assert_eq!(
+            config.hooks["pre-push"].tasks[0].run.script(),
+            "npm test && npm run build"
+        );
+    }
+
+    #[test]
+    fn test_migrate_simple_git_hooks() {
+        let package: serde_json::Value = serde_json::from_str(
+            r#"{
+                "name": "app",
+                "simple-git-hooks": {
+                    "pre-commit": "npx lint-staged",
+                    "pre-push": "npm test && npm run build",
+                    "commit-msg": "npx --no -- commitlint --edit $1",
+                    "preserveUnused": ["post-merge"]
+                }
+            }"#,
+        )
+        .unwrap();
+        let mut config = Config::default();
+
+        migrate_simple_git_hooks(&package["simple-git-hooks"], &mut config).unwrap();
+
+        let mut hooks: Vec<&str> = config.hooks.keys().map(String::as_str).collect();
+        hooks.sort();
+        assert_eq!(hooks, vec!["commit-msg", "pre-commit", "pre-push"]);
+        let pre_push = &config.hooks["pre-push"].tasks;
+        assert_eq!(pre_push.len(), 1);
+        assert_eq!(pre_push[0].run.script(), "npm test && npm run build");
+        assert_eq!(pre_push[0].name, "npm test");
+        assert_eq!(
+            config.hooks["commit-msg"].tasks[0].run.script(),
+            "npx --no -- commitlint --edit $1"
+        );
+    }
+
+    #[test]
+    fn test_infer_glob_unknown_command() {
+        assert_eq!(infer_glob("npm test"), None);
+        assert_eq!(infer_glob("cargo test"), None);
+        assert_eq!(infer_glob("eslint-plugin-check"), None);
+    }
+
+    #[test]
+    fn test_infer_glob_skips_project_wide_tools() {
+        assert_eq!(infer_glob("cargo fmt --check"), None);
+        assert_eq!(infer_glob("cargo clippy -- -D warnings"), None);
+        assert_eq!(infer_glob("npx tsc --noEmit"), None);
+    }
+
+    #[test]
+    fn test_migrated_eslint_task_matches_staged_files() {
+        let package: serde_json::Value =
+            serde_json::from_str(r#"{"simple-git-hooks": {"pre-commit": "npx eslint --fix"}}"#)
+                .unwrap();
+        let mut config = Config::default();
+        migrate_simple_git_hooks(&package["simple-git-hooks"], &mut config).unwrap();
+
+        let executor = TaskExecutor::without_git(
+            Settings::default(),
+            vec![PathBuf::from("src/app.ts"), PathBuf::from("README.md")],
+        );
+        let task = &config.hooks["pre-commit"].tasks[0];
+
+        assert_eq!(executor.task_files(task), vec![PathBuf::from("src/app.ts")]);
+    }
+}
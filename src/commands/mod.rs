@@ -2,10 +2,12 @@
 
 pub mod add;
 pub mod benchmark;
+pub mod config;
 pub mod init;
 pub mod install;
 pub mod list;
 pub mod migrate;
 pub mod run;
+pub mod status;
 pub mod uninstall;
 pub mod validate;
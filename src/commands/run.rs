@@ -1,67 +1,1353 @@
 //! Manually run a hook
 
-use crate::config;
-use crate::runner::TaskExecutor;
+use crate::cli::RunArgs;
+use crate::config::{self, ConfigParser, Hook, NotifyOn, NotifySettings, Settings};
+use crate::hooks::GitRepository;
+use crate::runner::{
+    github, json, junit, metrics, report, shuffle, staged_checks, webhook, HookResult,
+    IndexCheckout, LastFailed, LastRun, SkippedTask, TaskExecutor, TaskResult, Timings,
+};
+use crate::utils;
 use anyhow::{Context, Result};
 use colored::Colorize;
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime};
 
 /// Run a hook manually
-pub fn run(hook_name: String, files: Option<Vec<String>>, args: Vec<String>) -> Result<()> {
-    let config = config::load_config()?;
+pub fn run(args: RunArgs, verbose: bool) -> Result<()> {
+    let RunArgs {
+        hook: hook_name,
+        files,
+        no_git,
+        isolated,
+        max_parallel,
+        max_failures,
+        only,
+        no_deps,
+        allow_root,
+        bisect,
+        select_failed,
+        since_last_run,
+        list_files,
+        require_tasks,
+        carbon_detail,
+        junit: junit_path,
+        github_annotations,
+        output,
+        dump_env,
+        metrics_file,
+        shuffle: shuffle_seed,
+        args,
+        passthrough,
+    } = args;
+
+    let started_at = SystemTime::now();
+    let command_start = Instant::now();
+    let config = match config::load_config_with_scripts() {
+        Ok(config) => config,
+        Err(error) => {
+            let path = config::find_config_file();
+            let content = path.as_ref().and_then(|p| std::fs::read_to_string(p).ok());
+            let yaml = path.as_deref().is_some_and(ConfigParser::is_yaml);
+            return handle_config_error(error, content.as_deref(), yaml);
+        }
+    };
+
+    root_guard(utils::is_root(), config.settings.forbid_root, allow_root)?;
 
     let hook = config
         .hooks
         .get(&hook_name)
         .with_context(|| format!("Hook '{}' not found in configuration", hook_name))?;
+    let only = if select_failed {
+        failed_tasks(hook, &hook_name)
+    } else {
+        only
+    };
+    let mut hook = select_tasks(hook, &only, no_deps)?;
+    if bisect {
+        hook = bisect_hook(hook);
+    }
 
     println!("{} Running {} hook...", "→".cyan().bold(), hook_name.cyan());
     println!();
 
+    // Check the hook's condition before built-in checks or an isolated checkout can
+    // fail or take time on a hook that won't run
+    let branch = if no_git {
+        None
+    } else {
+        GitRepository::discover()
+            .ok()
+            .and_then(|repo| repo.current_branch().ok().flatten())
+    };
+    if !TaskExecutor::hook_enabled(&hook, branch.as_deref()) {
+        println!(
+            "  {} Skipping {} hook: condition '{}' is false",
+            "○".yellow(),
+            hook_name,
+            hook.condition.as_deref().unwrap_or_default().trim()
+        );
+        let skipped = hook
+            .tasks
+            .iter()
+            .map(|task| SkippedTask {
+                name: task.name.clone(),
+                reason: "the hook's condition is false".to_string(),
+            })
+            .collect();
+        let result = HookResult::new(Vec::new(), 0).with_skipped(skipped);
+        return ensure_tasks_ran(
+            &hook_name,
+            &result,
+            require_tasks || config.settings.require_tasks,
+        );
+    }
+
+    let settings = apply_overrides(&config.settings, max_parallel);
+
     // Create executor
-    let executor = if let Some(file_list) = files {
-        let paths: Vec<PathBuf> = file_list.into_iter().map(PathBuf::from).collect();
-        TaskExecutor::with_files(config.settings.clone(), paths)?
+    let mut checkout = None;
+    let executor = if no_git {
+        check_no_git(&hook, files.is_some())?;
+        TaskExecutor::without_git(
+            settings,
+            file_paths(files.unwrap_or_default(), Path::new("."))?,
+        )
+    } else if let Some(file_list) = files {
+        // Patterns are relative to the repository root, like task globs
+        let root = GitRepository::discover()
+            .ok()
+            .and_then(|repo| repo.workdir())
+            .unwrap_or_else(|| PathBuf::from("."));
+        TaskExecutor::with_files(settings, file_paths(file_list, &root)?)?
     } else {
-        TaskExecutor::new(config.settings.clone())?
+        let repo = GitRepository::discover()?;
+        // Built-in checks look at the index, so they only apply to staged files
+        if !list_files && hook.git_hook_name(&hook_name) == "pre-commit" {
+            staged_checks::check(&repo, &settings)?;
+        }
+        // An isolated run only sees the index, so partial staging can't skew it
+        if !isolated && !list_files {
+            if let Some(warning) = partially_staged_warning(&repo) {
+                eprintln!("{} {}\n", "Warning:".yellow().bold(), warning.yellow());
+            }
+        }
+        let executor = TaskExecutor::from_repo(settings, &repo);
+        if isolated {
+            let index_checkout = IndexCheckout::create(&repo)?;
+            let executor = executor.in_dir(index_checkout.path());
+            checkout = Some(index_checkout);
+            executor
+        } else {
+            executor
+        }
     };
 
-    // Add hook arguments if provided
-    let executor = executor.with_hook_args(args);
+    // `per_commit` tasks in pre-rebase run once for each commit being rebased
+    let mut executor = executor;
+    if !no_git
+        && hook.git_hook_name(&hook_name) == "pre-rebase"
+        && hook.tasks.iter().any(|t| t.per_commit)
+    {
+        executor = executor.with_commits(rebase_commits(&args)?);
+    }
+
+    // Add hook arguments and `--` passthrough arguments if provided
+    let message_file = args.first().map(PathBuf::from);
+    let mut executor = executor
+        .with_hook_args(args)
+        .with_passthrough_args(passthrough);
+
+    if let Some(max_failures) = max_failures {
+        executor = executor.with_max_failures(max_failures);
+    }
+    if let Some(dir) = &dump_env {
+        executor = executor.dump_env_to(dir);
+    }
+
+    // Randomize independent tasks; print the seed so failures can be reproduced
+    if let Some(seed) = shuffle_seed {
+        let seed = seed.unwrap_or_else(shuffle::random_seed);
+        println!("  {} Shuffling tasks with seed {}\n", "ℹ".dimmed(), seed);
+        executor = executor.with_shuffle(seed);
+    }
+
+    // Narrow down to files touched since the last successful run
+    if since_last_run {
+        let repo =
+            GitRepository::discover().context("--since-last-run requires a git repository")?;
+        match LastRun::load(&repo.state_dir(), &hook_name) {
+            Some(since) => {
+                let root = repo.workdir().unwrap_or_else(|| PathBuf::from("."));
+                executor = executor.modified_since(&root, since);
+            }
+            None => println!(
+                "  {} No previous run recorded, considering all files\n",
+                "ℹ".dimmed()
+            ),
+        }
+    }
+
+    if list_files {
+        print!("{}", file_listing(&executor, &hook));
+        return Ok(());
+    }
 
     // Run the hook
     let runtime = tokio::runtime::Runtime::new()?;
-    let result = runtime.block_on(executor.execute_hook(hook))?;
+    // Retries would hide which task failed first
+    let retries = if bisect {
+        0
+    } else {
+        config.settings.hook_retries
+    };
+    let (mut result, attempts) =
+        runtime.block_on(execute_with_retries(&executor, &hook, retries))?;
+    // Remove the scratch checkout now: exiting with a failure below skips destructors
+    let checkout_dir = checkout.as_ref().map(|c| c.path().to_path_buf());
+    drop(checkout);
+    // Once per run, not per attempt
+    if hook.append_summary {
+        if let Some(message_file) = &message_file {
+            let comment_char = GitRepository::discover()
+                .ok()
+                .and_then(|repo| repo.comment_char());
+            append_summary(message_file, &result, comment_char.as_deref())?;
+        }
+    }
+    // Total time includes config loading and git discovery, unlike the task wall time
+    result.stats = result
+        .stats
+        .with_command_time(command_start.elapsed().as_millis() as u64);
+    if attempts > 1 && result.success {
+        println!(
+            "  {} Passed on attempt {}/{}\n",
+            "↻".yellow(),
+            attempts,
+            retries + 1
+        );
+    }
 
-    // Display task results
-    for task_result in &result.tasks {
-        let status = if task_result.success {
-            format!("{} {}", "✓".green(), task_result.name)
-        } else {
-            format!("{} {}", "✗".red(), task_result.name)
-        };
-        println!("  {} ({}ms)", status, task_result.duration_ms);
+    if let Some(path) = &junit_path {
+        junit::write_report(path, &hook_name, &result)?;
+    }
+    if let Some(path) = &output {
+        json::write_report(path, &hook_name, &result)?;
+    }
+    if let Some(path) = &metrics_file {
+        metrics::write_metrics(path, &hook_name, &result)?;
+    }
 
-        // Show output for failed tasks
-        if !task_result.success {
-            if !task_result.stdout.is_empty() {
-                println!("{}", task_result.stdout);
+    // Display task results, under a header per stage when tasks declare one
+    if hook.tasks.iter().any(|t| t.stage.is_some()) {
+        for (stage, members) in report::group_by_stage(&hook, &result.tasks) {
+            println!("  {}", stage.unwrap_or("Other").bold());
+            for task_result in members {
+                print_task_result(task_result, "    ", verbose, &config.settings);
             }
-            if !task_result.stderr.is_empty() {
-                eprintln!("{}", task_result.stderr.red());
+        }
+    } else {
+        for task_result in &result.tasks {
+            print_task_result(task_result, "  ", verbose, &config.settings);
+        }
+    }
+
+    // Workflow commands go to stdout, where the Actions runner picks them up
+    if github_annotations || github::in_github_actions() {
+        let current_dir = std::env::current_dir().unwrap_or_default();
+        // An isolated checkout mirrors the repository, so paths are relative to it
+        let root = checkout_dir
+            .or_else(|| {
+                GitRepository::discover()
+                    .ok()
+                    .and_then(|repo| repo.workdir())
+            })
+            .unwrap_or_else(|| current_dir.clone());
+        for task_result in &result.tasks {
+            // Output paths are relative to the directory the task ran in
+            let dir = match hook.tasks.iter().find(|t| t.name == task_result.name) {
+                Some(task) => current_dir.join(executor.task_cwd(task)),
+                None => current_dir.clone(),
+            };
+            for annotation in github::annotations(task_result, &dir, &root) {
+                println!("{}", annotation);
             }
         }
     }
 
+    // Explain tasks that didn't run ("my linter didn't run")
+    if !result.skipped.is_empty() {
+        println!("  {}", "Skipped:".dimmed());
+        for skipped in &result.skipped {
+            println!(
+                "    {}",
+                format!("○ {}: {}", skipped.name, skipped.reason).dimmed()
+            );
+        }
+    }
+
+    if bisect {
+        match first_failure(&hook, &result) {
+            Some(failed) => println!(
+                "\n  {} First failing task: {} (after {} of {} tasks)",
+                "✗".red().bold(),
+                failed.name.bold(),
+                result.tasks.len(),
+                hook.tasks.len()
+            ),
+            None => println!("\n  {} No task failed", "✓".green().bold()),
+        }
+    }
+
     // Display stats
     println!(
         "{}",
         result.stats.format(config.settings.show_carbon_savings)
     );
+    if carbon_detail {
+        println!("{}", result.stats.carbon_savings.explain());
+    }
+
+    if let Some(notify_settings) = &config.settings.notify {
+        notify(notify_settings, result.success, result.stats.wall_time_ms);
+    }
+    if let Some(webhook_settings) = &config.settings.webhook {
+        if let Err(e) = webhook::post(webhook_settings, &hook_name, &result) {
+            eprintln!("{} {:#}", "Warning:".yellow().bold(), e);
+        }
+    }
+
+    let over_budget = budget_warning(&hook, result.stats.wall_time_ms);
+    if let Some(warning) = &over_budget {
+        eprintln!("{} {}", "Warning:".yellow().bold(), warning.yellow());
+    }
+
+    // Before recording state, so a run without tasks isn't saved as a passing baseline
+    ensure_tasks_ran(
+        &hook_name,
+        &result,
+        require_tasks || config.settings.require_tasks,
+    )?;
+
+    // Remember failures for --select-failed, successes for --since-last-run and task
+    // durations for slowdown warnings (best-effort)
+    let repo = if no_git {
+        None
+    } else {
+        GitRepository::discover().ok()
+    };
+    if let Some(repo) = repo {
+        let state_dir = repo.state_dir();
+        if let Err(e) = LastFailed::record(&state_dir, &hook_name, &result.tasks) {
+            tracing::warn!("Failed to record failed tasks: {}", e);
+        }
+        let timings = Timings::load(&state_dir, &hook_name);
+        for slowdown in Timings::slowdowns(&timings, &result.tasks) {
+            eprintln!(
+                "{} {}",
+                "Warning:".yellow().bold(),
+                slowdown.message().yellow()
+            );
+        }
+        if let Err(e) = Timings::record(&state_dir, &hook_name, &result.tasks) {
+            tracing::warn!("Failed to record task timings: {}", e);
+        }
+        if result.success {
+            if let Err(e) = LastRun::record(&state_dir, &hook_name, started_at) {
+                tracing::warn!("Failed to record last run: {}", e);
+            }
+        }
+    }
 
-    if !result.success {
+    let failed = !result.success || (over_budget.is_some() && hook.strict_time_budget);
+    if let Some(hint) = bypass_hint(
+        hook.git_hook_name(&hook_name),
+        failed,
+        config.settings.show_bypass_hint,
+    ) {
+        println!("{}", hint.dimmed());
+    }
+    if failed {
         std::process::exit(1);
     }
 
     Ok(())
 }
+
+/// The files each task's filters match, one block per task in config order
+fn file_listing(executor: &TaskExecutor, hook: &Hook) -> String {
+    let mut out = String::new();
+    for task in &hook.tasks {
+        let Some(filter) = task.file_filter() else {
+            out.push_str(&format!("  {} (no file filter)\n", task.name.bold()));
+            continue;
+        };
+        let files = executor.task_files(task);
+        out.push_str(&format!(
+            "  {} {}\n",
+            task.name.bold(),
+            format!(
+                "[{}] {} file{}",
+                filter,
+                files.len(),
+                if files.len() == 1 { "" } else { "s" }
+            )
+            .dimmed()
+        ));
+        for file in files {
+            out.push_str(&format!("    {}\n", file.display()));
+        }
+    }
+    out
+}
+
+/// Without git there are no staged files, so globbed tasks need an explicit `--files` list
+fn check_no_git(hook: &Hook, has_files: bool) -> Result<()> {
+    let globbed: Vec<&str> = hook
+        .tasks
+        .iter()
+        .filter(|t| t.filters_files())
+        .map(|t| t.name.as_str())
+        .collect();
+
+    if !has_files && !globbed.is_empty() {
+        anyhow::bail!(
+            "--no-git needs --files for tasks that filter files: {}",
+            globbed.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Turn `--files` arguments into paths, expanding `~` and `$VAR` / `${VAR}`, and glob
+/// patterns (e.g. `src/**/*.rs`) into the matching files under `root`
+fn file_paths(files: Vec<String>, root: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for file in &files {
+        let file = utils::expand_vars(file);
+        // Paths like `pages/[id].tsx` are literal when they exist
+        if !file.contains(['*', '?', '[']) || root.join(&file).exists() {
+            paths.push(PathBuf::from(file));
+            continue;
+        }
+
+        let pattern = root.join(&file);
+        let matches: Vec<PathBuf> = glob::glob(&pattern.to_string_lossy())
+            .with_context(|| format!("Invalid --files pattern '{}'", file))?
+            .filter_map(|entry| entry.ok())
+            .filter(|path| path.is_file())
+            .map(|path| {
+                path.strip_prefix(root)
+                    .map(Path::to_path_buf)
+                    .unwrap_or(path)
+            })
+            .collect();
+        if matches.is_empty() {
+            eprintln!(
+                "{} --files pattern '{}' matched no files",
+                "Warning:".yellow().bold(),
+                file
+            );
+        }
+        paths.extend(matches);
+    }
+    Ok(paths)
+}
+
+/// The commits a rebase will rewrite, from the pre-rebase arguments (upstream, optional branch)
+fn rebase_commits(args: &[String]) -> Result<Vec<String>> {
+    let upstream = args
+        .first()
+        .context("per_commit tasks in pre-rebase need the upstream argument from Git")?;
+    let repo = GitRepository::discover()?;
+    repo.commits_between(upstream, args.get(1).map(String::as_str))
+}
+
+/// Append a comment block with each task's outcome to the commit message file, using
+/// the repository's `core.commentChar` so git strips it when it records the commit
+fn append_summary(
+    message_file: &Path,
+    result: &HookResult,
+    comment_char: Option<&str>,
+) -> Result<()> {
+    let message = std::fs::read_to_string(message_file).with_context(|| {
+        format!(
+            "Failed to read commit message file: {}",
+            message_file.display()
+        )
+    })?;
+    let comment = summary_comment_char(comment_char, &message);
+
+    let passed = result.tasks.iter().filter(|t| t.success).count();
+    let mut summary = format!(
+        "\n{} fasthooks: {}/{} checks passed in {}ms\n",
+        comment,
+        passed,
+        result.tasks.len(),
+        result.total_duration_ms
+    );
+    for task in &result.tasks {
+        let mark = if task.success { "✓" } else { "✗" };
+        summary.push_str(&format!(
+            "{}   {} {} ({}ms)\n",
+            comment, mark, task.name, task.duration_ms
+        ));
+    }
+
+    let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(message_file)
+        .with_context(|| {
+            format!(
+                "Failed to open commit message file: {}",
+                message_file.display()
+            )
+        })?;
+    std::io::Write::write_all(&mut file, summary.as_bytes()).with_context(|| {
+        format!(
+            "Failed to write commit message file: {}",
+            message_file.display()
+        )
+    })
+}
+
+/// The comment character git strips from `message`: `core.commentChar`, or for `auto`
+/// the first of git's candidates that doesn't start a line of the message
+fn summary_comment_char(setting: Option<&str>, message: &str) -> String {
+    match setting.map(str::trim) {
+        None | Some("") => "#".to_string(),
+        Some("auto") => "#;@!$%^&|:"
+            .chars()
+            .find(|c| !message.lines().any(|line| line.starts_with(*c)))
+            .unwrap_or('#')
+            .to_string(),
+        Some(comment) => comment.to_string(),
+    }
+}
+
+/// With `show_bypass_hint`, how to skip a failed hook for the Git command it guards
+fn bypass_hint(git_hook: &str, failed: bool, enabled: bool) -> Option<String> {
+    if !failed || !enabled {
+        return None;
+    }
+    let command = match git_hook {
+        // `--no-verify` doesn't skip prepare-commit-msg, so it gets no hint
+        "pre-commit" | "commit-msg" => "git commit --no-verify",
+        "pre-merge-commit" => "git merge --no-verify",
+        "pre-push" => "git push --no-verify",
+        _ => return None,
+    };
+    Some(format!("To bypass: {}", command))
+}
+
+/// Under `--require-tasks`, fail a run in which every task was skipped or filtered out
+fn ensure_tasks_ran(hook_name: &str, result: &HookResult, required: bool) -> Result<()> {
+    if required && result.tasks.is_empty() {
+        anyhow::bail!(
+            "No tasks ran in hook '{}' ({} skipped); check the hook's globs and conditions",
+            hook_name,
+            result.skipped.len()
+        );
+    }
+    Ok(())
+}
+
+/// Warn when running as root, or refuse under `settings.forbid_root` unless `--allow-root`
+fn root_guard(is_root: bool, forbid_root: bool, allow_root: bool) -> Result<()> {
+    if !is_root || allow_root {
+        return Ok(());
+    }
+    if forbid_root {
+        anyhow::bail!(
+            "Refusing to run hooks as root (settings.forbid_root is set); pass --allow-root to override"
+        );
+    }
+    eprintln!(
+        "{} Running hooks as root; task commands get full privileges",
+        "Warning:".yellow().bold()
+    );
+    Ok(())
+}
+
+/// Apply command-line overrides to the configured settings
+fn apply_overrides(settings: &Settings, max_parallel: Option<usize>) -> Settings {
+    let mut settings = settings.clone();
+    if let Some(max_parallel) = max_parallel {
+        settings.max_parallel = max_parallel;
+    }
+    settings
+}
+
+/// Force one-at-a-time execution that stops at the first failure, for `--bisect`
+fn bisect_hook(mut hook: Hook) -> Hook {
+    hook.parallel = Some(false);
+    hook.fail_fast = Some(true);
+    hook
+}
+
+/// The failure that stopped a bisect run (failures of `allow_failure` tasks don't count)
+fn first_failure<'a>(hook: &Hook, result: &'a HookResult) -> Option<&'a TaskResult> {
+    result.tasks.iter().find(|r| {
+        !r.success
+            && !hook
+                .tasks
+                .iter()
+                .any(|t| t.name == r.name && t.allow_failure)
+    })
+}
+
+/// Tasks of the hook that failed last time and still exist, for `--select-failed`
+fn failed_tasks(hook: &Hook, hook_name: &str) -> Vec<String> {
+    let failed = GitRepository::discover()
+        .map(|repo| LastFailed::load(&repo.state_dir(), hook_name))
+        .unwrap_or_default();
+    let existing: Vec<String> = failed
+        .into_iter()
+        .filter(|name| hook.tasks.iter().any(|t| &t.name == name))
+        .collect();
+
+    if existing.is_empty() {
+        println!(
+            "  {} No failed tasks recorded, running all tasks\n",
+            "ℹ".dimmed()
+        );
+    }
+    existing
+}
+
+/// Narrow a hook down to the tasks selected with `--only`.
+///
+/// Selected tasks keep their transitive dependencies unless `no_deps` is set,
+/// in which case all dependencies are dropped and tasks run standalone.
+fn select_tasks(hook: &Hook, only: &[String], no_deps: bool) -> Result<Hook> {
+    let mut selected = hook.clone();
+
+    if !only.is_empty() {
+        if let Some(missing) = only
+            .iter()
+            .find(|name| !hook.tasks.iter().any(|t| &t.name == *name))
+        {
+            anyhow::bail!("Task '{}' not found in hook", missing);
+        }
+
+        let mut wanted: HashSet<&str> = only.iter().map(String::as_str).collect();
+        if !no_deps {
+            let mut pending: Vec<&str> = wanted.iter().copied().collect();
+            while let Some(name) = pending.pop() {
+                if let Some(task) = hook.tasks.iter().find(|t| t.name == name) {
+                    for dep in &task.depends_on {
+                        if wanted.insert(dep) {
+                            pending.push(dep);
+                        }
+                    }
+                }
+            }
+        }
+
+        selected.tasks.retain(|t| wanted.contains(t.name.as_str()));
+    }
+
+    if no_deps {
+        for task in &mut selected.tasks {
+            task.depends_on.clear();
+        }
+    }
+
+    Ok(selected)
+}
+
+/// Print a single task result, with output for failed tasks
+fn print_task_result(task_result: &TaskResult, indent: &str, verbose: bool, settings: &Settings) {
+    let name = if settings.task_colors {
+        task_result
+            .name
+            .color(report::task_color(&task_result.name))
+            .to_string()
+    } else {
+        task_result.name.clone()
+    };
+    let status = if task_result.success {
+        format!("{} {}", "✓".green(), name)
+    } else {
+        format!("{} {}", "✗".red(), name)
+    };
+    println!("{}{} {}", indent, status, timing(task_result));
+    if verbose && !task_result.command.is_empty() {
+        println!(
+            "{}  {}",
+            indent,
+            format!("$ {}", task_result.command).dimmed()
+        );
+    }
+
+    // Show output for failed tasks
+    if !task_result.success {
+        if !task_result.stdout.is_empty() {
+            println!("{}", task_result.stdout);
+        }
+        if !task_result.stderr.is_empty() {
+            if settings.dedupe_output {
+                eprintln!("{}", report::dedupe_lines(&task_result.stderr).red());
+            } else {
+                eprintln!("{}", task_result.stderr.red());
+            }
+        }
+    }
+}
+
+/// Parenthesized duration, prefixed with the file count for globbed tasks
+fn timing(task_result: &TaskResult) -> String {
+    match task_result.file_count {
+        Some(1) => format!("(1 file, {}ms)", task_result.duration_ms),
+        Some(count) => format!("({} files, {}ms)", count, task_result.duration_ms),
+        None => format!("({}ms)", task_result.duration_ms),
+    }
+}
+
+/// Run a hook, re-running it while it fails up to `retries` extra times.
+///
+/// Returns the last result together with the number of attempts made.
+async fn execute_with_retries(
+    executor: &TaskExecutor,
+    hook: &Hook,
+    retries: u32,
+) -> Result<(HookResult, u32)> {
+    let mut attempt = 1;
+    loop {
+        let result = executor.execute_hook(hook).await?;
+        if result.success || attempt > retries {
+            return Ok((result, attempt));
+        }
+
+        attempt += 1;
+        println!(
+            "  {} Hook failed, retrying (attempt {}/{})\n",
+            "↻".yellow(),
+            attempt,
+            retries + 1
+        );
+    }
+}
+
+/// Run the configured notification command (best-effort, never fails the hook)
+fn notify(settings: &NotifySettings, success: bool, duration_ms: u64) {
+    let wanted = match settings.on {
+        NotifyOn::Always => true,
+        NotifyOn::Failure => !success,
+        NotifyOn::Success => success,
+    };
+    if !wanted {
+        return;
+    }
+
+    let command = settings
+        .command
+        .replace("{status}", if success { "success" } else { "failure" })
+        .replace("{duration}", &format!("{}ms", duration_ms));
+    let (shell, shell_arg) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+
+    match std::process::Command::new(shell)
+        .arg(shell_arg)
+        .arg(&command)
+        .status()
+    {
+        Ok(status) if !status.success() => {
+            tracing::warn!("Notify command exited with {}", status);
+        }
+        Err(e) => tracing::warn!("Failed to run notify command: {}", e),
+        Ok(_) => {}
+    }
+}
+
+/// Decide whether a config load failure blocks the hook.
+///
+/// The config is broken, so `stop_on_config_error` is read leniently from the raw
+/// TOML; if even that fails, we fall back to the safe default and fail the hook.
+fn handle_config_error(error: anyhow::Error, content: Option<&str>, yaml: bool) -> Result<()> {
+    let stop = content
+        .and_then(|c| {
+            if yaml {
+                let value = serde_yaml_ng::from_str::<serde_yaml_ng::Value>(c).ok()?;
+                value
+                    .get("settings")?
+                    .get("stop_on_config_error")?
+                    .as_bool()
+            } else {
+                let value = c.parse::<toml::Value>().ok()?;
+                value
+                    .get("settings")?
+                    .get("stop_on_config_error")?
+                    .as_bool()
+            }
+        })
+        .unwrap_or(true);
+
+    if stop {
+        return Err(error);
+    }
+
+    eprintln!(
+        "{} Invalid configuration, skipping hook: {:#}",
+        "Warning:".yellow().bold(),
+        error
+    );
+    Ok(())
+}
+
+/// Warn that tasks will see working-tree content of staged files that have unstaged edits
+fn partially_staged_warning(repo: &GitRepository) -> Option<String> {
+    let files = repo.partially_staged_files().ok()?;
+    if files.is_empty() {
+        return None;
+    }
+    let list: Vec<String> = files
+        .iter()
+        .map(|f| format!("  - {}", f.display()))
+        .collect();
+    Some(format!(
+        "{} staged file{} also {} unstaged changes; tasks see the working-tree version \
+         (use --isolated to check only what is staged):\n{}",
+        files.len(),
+        if files.len() == 1 { "" } else { "s" },
+        if files.len() == 1 { "has" } else { "have" },
+        list.join("\n")
+    ))
+}
+
+/// Describe how far a run went over the hook's `time_budget_ms`, if it did
+fn budget_warning(hook: &Hook, wall_time_ms: u64) -> Option<String> {
+    let budget = hook.time_budget_ms?;
+    (wall_time_ms > budget).then(|| {
+        format!(
+            "Hook took {}ms, over its time budget of {}ms by {}ms",
+            wall_time_ms,
+            budget,
+            wall_time_ms - budget
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Cli, Commands};
+    use crate::config::Task;
+    use crate::runner::ExecutionStats;
+    use clap::Parser;
+
+    fn test_hook() -> Hook {
+        let content = r#"
+version = "1"
+
+[hooks.pre-commit]
+[[hooks.pre-commit.tasks]]
+name = "lint"
+run = "npm run lint"
+
+[[hooks.pre-commit.tasks]]
+name = "test"
+run = "npm test"
+depends_on = ["lint"]
+
+[[hooks.pre-commit.tasks]]
+name = "format"
+run = "npm run format"
+"#;
+        let config = ConfigParser::parse_toml(content).unwrap();
+        config.hooks["pre-commit"].clone()
+    }
+
+    #[test]
+    fn test_select_tasks_only_includes_dependencies() {
+        let hook = select_tasks(&test_hook(), &["test".to_string()], false).unwrap();
+        let names: Vec<&str> = hook.tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["lint", "test"]);
+    }
+
+    #[test]
+    fn test_select_tasks_only_no_deps() {
+        let hook = select_tasks(&test_hook(), &["test".to_string()], true).unwrap();
+        assert_eq!(hook.tasks.len(), 1);
+        assert_eq!(hook.tasks[0].name, "test");
+        assert!(hook.tasks[0].depends_on.is_empty());
+    }
+
+    #[test]
+    fn test_select_tasks_unknown_task() {
+        let result = select_tasks(&test_hook(), &["deploy".to_string()], false);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_bypass_hint_only_on_failure_when_enabled() {
+        assert_eq!(
+            bypass_hint("pre-commit", true, true).as_deref(),
+            Some("To bypass: git commit --no-verify")
+        );
+        assert_eq!(
+            bypass_hint("pre-push", true, true).as_deref(),
+            Some("To bypass: git push --no-verify")
+        );
+        assert_eq!(bypass_hint("pre-commit", false, true), None);
+        assert_eq!(bypass_hint("pre-commit", true, false), None);
+        // Hooks Git can't skip get no hint
+        assert_eq!(bypass_hint("post-checkout", true, true), None);
+        assert_eq!(bypass_hint("prepare-commit-msg", true, true), None);
+    }
+
+    #[test]
+    fn test_append_summary_to_commit_message() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let message_file = temp_dir.path().join("COMMIT_EDITMSG");
+        std::fs::write(&message_file, "Add login form\n").unwrap();
+        let tasks = vec![TaskResult::success(
+            "lint".to_string(),
+            String::new(),
+            String::new(),
+            12,
+        )];
+        let result = HookResult::new(tasks, 15);
+
+        append_summary(&message_file, &result, None).unwrap();
+        let message = std::fs::read_to_string(&message_file).unwrap();
+        assert_eq!(
+            message,
+            "Add login form\n\n# fasthooks: 1/1 checks passed in 15ms\n#   ✓ lint (12ms)\n"
+        );
+
+        // A custom comment character is used instead of `#`
+        std::fs::write(&message_file, "Add login form\n").unwrap();
+        append_summary(&message_file, &result, Some(";")).unwrap();
+        let message = std::fs::read_to_string(&message_file).unwrap();
+        assert!(message.ends_with("\n; fasthooks: 1/1 checks passed in 15ms\n;   ✓ lint (12ms)\n"));
+    }
+
+    #[test]
+    fn test_summary_comment_char_auto() {
+        assert_eq!(summary_comment_char(None, "Fix #12"), "#");
+        assert_eq!(summary_comment_char(Some("%"), "Fix"), "%");
+        // `auto` skips characters that start a line of the message
+        assert_eq!(
+            summary_comment_char(Some("auto"), "Fix\n# not a comment"),
+            ";"
+        );
+    }
+
+    #[test]
+    fn test_budget_warning_when_over_budget() {
+        let mut hook = test_hook();
+        let tasks = vec![TaskResult::success(
+            "lint".to_string(),
+            String::new(),
+            String::new(),
+            5,
+        )];
+        let stats = ExecutionStats::from_tasks(&tasks, 5);
+
+        assert!(budget_warning(&hook, stats.wall_time_ms).is_none());
+
+        hook.time_budget_ms = Some(1);
+        let warning = budget_warning(&hook, stats.wall_time_ms).unwrap();
+        assert!(warning.contains("over its time budget of 1ms"));
+
+        hook.time_budget_ms = Some(1000);
+        assert!(budget_warning(&hook, stats.wall_time_ms).is_none());
+    }
+
+    #[test]
+    fn test_config_error_fails_by_default() {
+        let content = "version = \"1\"\n[settings]\nparallel = \"yes\"\n";
+        let error = ConfigParser::parse_toml(content).unwrap_err();
+        assert!(handle_config_error(error, Some(content), false).is_err());
+
+        // Unparseable TOML can't opt out, so it keeps the safe default
+        let content = "[settings\nstop_on_config_error = false\n";
+        assert!(handle_config_error(anyhow::anyhow!("parse error"), Some(content), false).is_err());
+    }
+
+    #[test]
+    fn test_config_error_warns_when_allowed() {
+        let content =
+            "version = \"1\"\n[settings]\nstop_on_config_error = false\nparallel = \"yes\"\n";
+        let error = ConfigParser::parse_toml(content).unwrap_err();
+        assert!(handle_config_error(error, Some(content), false).is_ok());
+
+        let content = "version: \"1\"\nsettings:\n  stop_on_config_error: false\n  parallel: yes\n";
+        let error = ConfigParser::parse_yaml(content).unwrap_err();
+        assert!(handle_config_error(error, Some(content), true).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_hook_retries_until_success() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let counter = temp_dir.path().join("attempts");
+        let content = format!(
+            r#"
+version = "1"
+
+[hooks.pre-push]
+[[hooks.pre-push.tasks]]
+name = "flaky"
+run = "echo x >> {0} && [ $(wc -l < {0}) -ge 2 ]"
+"#,
+            counter.display()
+        );
+        let config = ConfigParser::parse_toml(&content).unwrap();
+        let hook = &config.hooks["pre-push"];
+        let executor = TaskExecutor::with_files(config.settings.clone(), Vec::new()).unwrap();
+
+        let (result, attempts) = execute_with_retries(&executor, hook, 0).await.unwrap();
+        assert!(!result.success);
+        assert_eq!(attempts, 1);
+
+        std::fs::remove_file(&counter).unwrap();
+        let (result, attempts) = execute_with_retries(&executor, hook, 3).await.unwrap();
+        assert!(result.success);
+        assert_eq!(attempts, 2);
+        let runs = std::fs::read_to_string(&counter).unwrap();
+        assert_eq!(runs.lines().count(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_notify_receives_status() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let out = temp_dir.path().join("notified");
+        let mut settings = NotifySettings {
+            on: NotifyOn::Always,
+            command: format!("echo {{status}} {{duration}} > {}", out.display()),
+        };
+
+        notify(&settings, false, 1500);
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "failure 1500ms\n");
+
+        // Filtered out by `on`
+        std::fs::remove_file(&out).unwrap();
+        settings.on = NotifyOn::Failure;
+        notify(&settings, true, 10);
+        assert!(!out.exists());
+
+        // A failing notify command is only logged
+        settings.command = "exit 3".to_string();
+        notify(&settings, false, 10);
+    }
+
+    #[test]
+    fn test_timing_includes_file_count() {
+        let result = TaskResult::success("lint".to_string(), String::new(), String::new(), 120);
+        assert_eq!(timing(&result), "(120ms)");
+        assert_eq!(
+            timing(&result.clone().with_file_count(3)),
+            "(3 files, 120ms)"
+        );
+        assert_eq!(timing(&result.with_file_count(1)), "(1 file, 120ms)");
+    }
+
+    #[test]
+    fn test_file_listing_shows_matches_without_running() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let marker = temp_dir.path().join("ran");
+        let hook = Hook {
+            tasks: vec![
+                Task {
+                    name: "lint".to_string(),
+                    run: format!("touch {}", marker.display()).into(),
+                    glob: Some("*.rs".to_string()),
+                    ..Default::default()
+                },
+                Task {
+                    name: "audit".to_string(),
+                    run: format!("touch {}", marker.display()).into(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let executor = TaskExecutor::without_git(
+            Default::default(),
+            vec![PathBuf::from("src/main.rs"), PathBuf::from("README.md")],
+        );
+
+        let listing = file_listing(&executor, &hook);
+        assert!(listing.contains("lint"));
+        assert!(listing.contains("    src/main.rs\n"));
+        assert!(!listing.contains("README.md"));
+        assert!(listing.contains("(no file filter)"));
+        assert!(!marker.exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_select_failed_reruns_only_failed_task() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let state_dir = temp_dir.path().join("state");
+        let fixed = temp_dir.path().join("fixed");
+        let content = format!(
+            r#"
+version = "1"
+
+[settings]
+fail_fast = false
+
+[hooks.pre-commit]
+[[hooks.pre-commit.tasks]]
+name = "fmt"
+run = "true"
+
+[[hooks.pre-commit.tasks]]
+name = "lint"
+run = "test -f {}"
+"#,
+            fixed.display()
+        );
+        let config = ConfigParser::parse_toml(&content).unwrap();
+        let hook = &config.hooks["pre-commit"];
+        let executor = TaskExecutor::with_files(config.settings.clone(), Vec::new()).unwrap();
+
+        // First run: lint fails and is recorded
+        let result = executor.execute_hook(hook).await.unwrap();
+        LastFailed::record(&state_dir, "pre-commit", &result.tasks).unwrap();
+
+        // Fix it and re-run just the failed task
+        std::fs::write(&fixed, "").unwrap();
+        let only = LastFailed::load(&state_dir, "pre-commit");
+        let selected = select_tasks(hook, &only, false).unwrap();
+        let result = executor.execute_hook(&selected).await.unwrap();
+
+        let names: Vec<&str> = result.tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["lint"]);
+        assert!(result.success);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_bisect_stops_at_first_failure() {
+        let content = r#"
+version = "1"
+
+[settings]
+parallel = true
+fail_fast = false
+
+[hooks.pre-commit]
+[[hooks.pre-commit.tasks]]
+name = "fmt"
+run = "true"
+
+[[hooks.pre-commit.tasks]]
+name = "lint"
+run = "false"
+depends_on = ["fmt"]
+
+[[hooks.pre-commit.tasks]]
+name = "test"
+run = "false"
+"#;
+        let config = ConfigParser::parse_toml(content).unwrap();
+        let hook = bisect_hook(config.hooks["pre-commit"].clone());
+        let executor = TaskExecutor::with_files(config.settings.clone(), Vec::new()).unwrap();
+
+        let result = executor.execute_hook(&hook).await.unwrap();
+        let names: Vec<&str> = result.tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["fmt", "lint"]);
+        assert_eq!(first_failure(&hook, &result).unwrap().name, "lint");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_max_parallel_flag_serializes_tasks() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let log = temp_dir.path().join("log");
+        let task = |name: &str| {
+            format!(
+                "[[hooks.pre-commit.tasks]]\nname = \"{name}\"\nrun = \"echo start >> '{log}'; sleep 0.2; echo end >> '{log}'\"\n",
+                name = name,
+                log = log.display()
+            )
+        };
+        let content = format!(
+            "[settings]\nparallel = true\n\n[hooks.pre-commit]\n{}{}",
+            task("first"),
+            task("second")
+        );
+        let config = ConfigParser::parse_toml(&content).unwrap();
+
+        let cli = Cli::parse_from(["fasthooks", "run", "pre-commit", "--max-parallel", "1"]);
+        let Some(Commands::Run(args)) = cli.command else {
+            panic!("expected run");
+        };
+        let settings = apply_overrides(&config.settings, args.max_parallel);
+        assert_eq!(settings.max_parallel, 1);
+
+        let executor = TaskExecutor::without_git(settings, Vec::new());
+        let result = executor
+            .execute_hook(&config.hooks["pre-commit"])
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(
+            std::fs::read_to_string(&log).unwrap(),
+            "start\nend\nstart\nend\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_partially_staged_warning() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let raw = git2::Repository::init(root).unwrap();
+        std::fs::write(root.join("partial.rs"), "fn a() {}\n").unwrap();
+        std::fs::write(root.join("full.rs"), "fn b() {}\n").unwrap();
+        let mut index = raw.index().unwrap();
+        index.add_path(std::path::Path::new("partial.rs")).unwrap();
+        index.add_path(std::path::Path::new("full.rs")).unwrap();
+        index.write().unwrap();
+        let repo = GitRepository::open(root).unwrap();
+
+        assert!(partially_staged_warning(&repo).is_none());
+
+        std::fs::write(root.join("partial.rs"), "fn a() { todo!() }\n").unwrap();
+        let warning = partially_staged_warning(&repo).unwrap();
+        assert!(warning.starts_with("1 staged file also has unstaged changes"));
+        assert!(warning.contains("  - partial.rs"));
+        assert!(!warning.contains("full.rs"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_require_tasks_fails_when_all_skipped() {
+        let hook = Hook {
+            tasks: vec![Task {
+                name: "stylelint".to_string(),
+                run: "true".into(),
+                glob: Some("*.css".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let executor =
+            TaskExecutor::without_git(Default::default(), vec![PathBuf::from("src/main.rs")]);
+        let result = executor.execute_hook(&hook).await.unwrap();
+        assert!(result.success);
+
+        let err = ensure_tasks_ran("pre-commit", &result, true).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "No tasks ran in hook 'pre-commit' (1 skipped); check the hook's globs and conditions"
+        );
+        assert!(ensure_tasks_ran("pre-commit", &result, false).is_ok());
+    }
+
+    #[test]
+    fn test_root_guard() {
+        assert!(root_guard(false, true, false).is_ok());
+        assert!(
+            root_guard(true, false, false).is_ok(),
+            "only warns by default"
+        );
+
+        let error = root_guard(true, true, false).unwrap_err().to_string();
+        assert!(error.contains("--allow-root"));
+        assert!(root_guard(true, true, true).is_ok());
+    }
+
+    #[test]
+    fn test_file_paths_expand_home_and_vars() {
+        std::env::set_var("FASTHOOKS_TEST_FILES_DIR", "generated");
+        let paths = file_paths(
+            vec![
+                "src/main.rs".to_string(),
+                "$FASTHOOKS_TEST_FILES_DIR/out.rs".to_string(),
+                "~/foo".to_string(),
+            ],
+            Path::new("."),
+        )
+        .unwrap();
+
+        assert_eq!(paths[0], PathBuf::from("src/main.rs"));
+        assert_eq!(paths[1], PathBuf::from("generated/out.rs"));
+        let home = utils::expand_vars("~");
+        if home != "~" {
+            assert_eq!(paths[2], PathBuf::from(format!("{}/foo", home)));
+        }
+    }
+
+    #[test]
+    fn test_file_paths_expand_globs() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("src/runner")).unwrap();
+        for file in [
+            "src/main.rs",
+            "src/runner/mod.rs",
+            "src/notes.md",
+            "build.rs",
+        ] {
+            std::fs::write(root.join(file), "").unwrap();
+        }
+
+        let paths = file_paths(
+            vec!["src/**/*.rs".to_string(), "README.md".to_string()],
+            root,
+        )
+        .unwrap();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("src/main.rs"),
+                PathBuf::from("src/runner/mod.rs"),
+                PathBuf::from("README.md"),
+            ]
+        );
+
+        assert!(file_paths(vec!["docs/*.md".to_string()], root)
+            .unwrap()
+            .is_empty());
+
+        // An existing path with brackets is used as-is rather than as a character class
+        std::fs::create_dir_all(root.join("pages")).unwrap();
+        std::fs::write(root.join("pages/[id].tsx"), "").unwrap();
+        std::fs::write(root.join("pages/i.tsx"), "").unwrap();
+        assert_eq!(
+            file_paths(vec!["pages/[id].tsx".to_string()], root).unwrap(),
+            vec![PathBuf::from("pages/[id].tsx")]
+        );
+    }
+
+    #[test]
+    fn test_check_no_git_requires_files_for_globbed_tasks() {
+        let content = r#"
+version = "1"
+
+[hooks.pre-commit]
+[[hooks.pre-commit.tasks]]
+name = "fmt"
+run = "cargo fmt --check"
+
+[[hooks.pre-commit.tasks]]
+name = "lint"
+run = "clippy-driver"
+glob = "*.rs"
+"#;
+        let config = ConfigParser::parse_toml(content).unwrap();
+        let hook = &config.hooks["pre-commit"];
+
+        let error = check_no_git(hook, false).unwrap_err().to_string();
+        assert!(error.contains("lint"));
+        assert!(!error.contains("fmt"));
+        assert!(check_no_git(hook, true).is_ok());
+        assert!(check_no_git(
+            &select_tasks(hook, &["fmt".to_string()], false).unwrap(),
+            false
+        )
+        .is_ok());
+    }
+}
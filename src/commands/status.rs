@@ -0,0 +1,74 @@
+//! Status overview, shown when no subcommand is given
+
+use crate::config::{self, ConfigParser};
+use crate::hooks::HookInstaller;
+use anyhow::Result;
+use colored::Colorize;
+
+/// Show the repository, configuration and installed hooks at a glance
+pub fn run() -> Result<()> {
+    println!("{}", "FastHooks Status".bold());
+    println!("{}", "═".repeat(40));
+    println!();
+
+    let installed = match HookInstaller::new() {
+        Ok(installer) => {
+            println!("  {} Git repository found", "✓".green());
+            Some(installer.installed_hooks())
+        }
+        Err(_) => {
+            println!("  {} Not inside a Git repository", "○".yellow());
+            None
+        }
+    };
+
+    match config::find_config_file() {
+        Some(path) => {
+            println!("  {} Config file: {}", "✓".green(), path.display());
+            match ConfigParser::parse_file(&path) {
+                Ok(config) => {
+                    println!();
+                    println!("{}", "Configured hooks:".bold());
+                    if config.hooks.is_empty() {
+                        println!("  (none)");
+                    }
+                    for (hook_name, hook) in &config.hooks {
+                        let is_installed = installed.as_ref().is_some_and(|hooks| {
+                            hooks
+                                .iter()
+                                .any(|h| h.as_str() == hook.git_hook_name(hook_name))
+                        });
+                        let marker = if is_installed {
+                            "●".green()
+                        } else {
+                            "○".yellow()
+                        };
+                        println!(
+                            "  {} {} ({} task{})",
+                            marker,
+                            hook_name.cyan(),
+                            hook.tasks.len(),
+                            if hook.tasks.len() == 1 { "" } else { "s" }
+                        );
+                    }
+                }
+                Err(e) => println!("  {} Config is invalid: {}", "✗".red(), e),
+            }
+        }
+        None => {
+            println!("  {} No fasthooks.toml found", "○".yellow());
+            println!("    Create one with: {}", "fasthooks init".cyan());
+        }
+    }
+
+    if let Some(hooks) = installed.filter(|hooks| !hooks.is_empty()) {
+        let names: Vec<&str> = hooks.iter().map(|h| h.as_str()).collect();
+        println!();
+        println!("{} {}", "Installed hooks:".bold(), names.join(", "));
+    }
+
+    println!();
+    println!("Run {} to see all commands.", "fasthooks --help".cyan());
+
+    Ok(())
+}
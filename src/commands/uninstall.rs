@@ -1,16 +1,33 @@
 //! Uninstall FastHooks
 
-use crate::config::CONFIG_FILE_NAME;
-use crate::hooks::HookInstaller;
-use anyhow::Result;
+use crate::config::{ALT_CONFIG_FILE_NAMES, CONFIG_FILE_NAME};
+use crate::hooks::{GitRepository, HookInstaller};
+use anyhow::{Context, Result};
 use colored::Colorize;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
 
 /// Run the uninstall command
-pub fn run() -> Result<()> {
+pub fn run(purge: bool, yes: bool) -> Result<()> {
+    if purge && !yes && !confirm_purge()? {
+        println!("Aborted, nothing was removed.");
+        return Ok(());
+    }
+
     let installer = HookInstaller::new()?;
     installer.uninstall_all()?;
 
     println!("{} Uninstalled all FastHooks git hooks", "✓".green().bold());
+
+    if purge {
+        let repo = GitRepository::discover()?;
+        for path in purge_files(&repo)? {
+            println!("{} Removed {}", "✓".green().bold(), path.display());
+        }
+        return Ok(());
+    }
+
     println!();
     println!(
         "Note: {} was not removed. Delete it manually if needed.",
@@ -19,3 +36,78 @@ pub fn run() -> Result<()> {
 
     Ok(())
 }
+
+/// Ask for confirmation before deleting the configuration and state
+fn confirm_purge() -> Result<bool> {
+    print!(
+        "This removes {} and FastHooks state from this repository. Continue? [y/N] ",
+        CONFIG_FILE_NAME.cyan()
+    );
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+/// Remove FastHooks-owned files: the config file(s) at the repository root and
+/// the state directory under `.git/`. Hook scripts in `.fasthooks/` are user
+/// content and are left in place.
+fn purge_files(repo: &GitRepository) -> Result<Vec<PathBuf>> {
+    let mut removed = Vec::new();
+
+    if let Some(root) = repo.workdir() {
+        for name in std::iter::once(&CONFIG_FILE_NAME).chain(ALT_CONFIG_FILE_NAMES) {
+            let path = root.join(name);
+            if path.is_file() {
+                fs::remove_file(&path)
+                    .with_context(|| format!("Failed to remove {}", path.display()))?;
+                removed.push(path);
+            }
+        }
+    }
+
+    let state_dir = repo.state_dir();
+    if state_dir.is_dir() {
+        fs::remove_dir_all(&state_dir)
+            .with_context(|| format!("Failed to remove {}", state_dir.display()))?;
+        removed.push(state_dir);
+    }
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::HookType;
+    use git2::Repository;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_purge_removes_config_and_state() {
+        let temp_dir = TempDir::new().unwrap();
+        Repository::init(temp_dir.path()).unwrap();
+        let root = temp_dir.path();
+
+        let installer = HookInstaller::from_repo(GitRepository::open(root).unwrap());
+        installer.install_hook(HookType::PreCommit).unwrap();
+        fs::write(root.join(CONFIG_FILE_NAME), "version = \"1\"\n").unwrap();
+        fs::create_dir_all(root.join(".git/fasthooks")).unwrap();
+        fs::write(root.join(".git/fasthooks/last-run.json"), "{}").unwrap();
+        fs::create_dir_all(root.join(".fasthooks")).unwrap();
+        fs::write(root.join(".fasthooks/pre-commit.sh"), "true\n").unwrap();
+        fs::write(root.join("README.md"), "# project\n").unwrap();
+
+        installer.uninstall_all().unwrap();
+        let removed = purge_files(&GitRepository::open(root).unwrap()).unwrap();
+
+        assert_eq!(removed.len(), 2);
+        assert!(!root.join(CONFIG_FILE_NAME).exists());
+        assert!(!root.join(".git/fasthooks").exists());
+        assert!(!root.join(".git/hooks/pre-commit").exists());
+        // Files that aren't ours are untouched
+        assert!(root.join(".fasthooks/pre-commit.sh").exists());
+        assert!(root.join("README.md").exists());
+    }
+}
@@ -1,11 +1,72 @@
 //! Configuration validation command
 
-use crate::config::{self, ConfigParser};
+use crate::config::{self, Config, ConfigParser, ValidationError};
+use crate::runner::TaskExecutor;
 use anyhow::Result;
 use colored::Colorize;
+use std::path::Path;
+
+/// Cargo subcommands that ship with cargo or rustup and are never aliases
+const CARGO_BUILTINS: &[&str] = &[
+    "add",
+    "bench",
+    "build",
+    "check",
+    "clean",
+    "clippy",
+    "config",
+    "doc",
+    "fetch",
+    "fix",
+    "fmt",
+    "generate-lockfile",
+    "help",
+    "init",
+    "install",
+    "locate-project",
+    "login",
+    "logout",
+    "metadata",
+    "miri",
+    "new",
+    "owner",
+    "package",
+    "pkgid",
+    "publish",
+    "remove",
+    "report",
+    "run",
+    "rustc",
+    "rustdoc",
+    "search",
+    "test",
+    "tree",
+    "uninstall",
+    "update",
+    "vendor",
+    "verify-project",
+    "version",
+    "yank",
+    // Built-in short aliases
+    "b",
+    "c",
+    "d",
+    "r",
+    "rm",
+    "t",
+];
+
+/// A script or alias a task command relies on
+#[derive(Debug, PartialEq, Eq)]
+pub enum ScriptRef<'a> {
+    /// `npm run <name>` (also pnpm, yarn and bun)
+    Npm(&'a str),
+    /// `cargo <name>` for a non-builtin subcommand
+    Cargo(&'a str),
+}
 
 /// Run the validate command
-pub fn run() -> Result<()> {
+pub fn run(explain: bool, check_scripts: bool) -> Result<()> {
     println!("{} Validating configuration...\n", "→".cyan().bold());
 
     // Find and load config
@@ -55,6 +116,9 @@ pub fn run() -> Result<()> {
                     hook.tasks.len(),
                     if hook.tasks.len() == 1 { "" } else { "s" }
                 );
+                if let Some(description) = &hook.description {
+                    println!("      {}", description.dimmed());
+                }
 
                 for task in &hook.tasks {
                     let mut extras = Vec::new();
@@ -99,6 +163,34 @@ pub fn run() -> Result<()> {
                     "no".red()
                 }
             );
+
+            let warnings = ConfigParser::warnings(&config);
+            if !warnings.is_empty() {
+                println!();
+                print!("{}", ConfigParser::format_validation_warnings(&warnings));
+            }
+
+            if check_scripts {
+                let root = config_path.parent().unwrap_or(Path::new("."));
+                let missing = missing_scripts(&config, root);
+                println!();
+                if missing.is_empty() {
+                    println!("{} All referenced scripts exist", "✓".green().bold());
+                } else {
+                    print!("{}", ConfigParser::format_validation_warnings(&missing));
+                }
+            }
+
+            if explain {
+                // Explain the config `run` sees, with scripts, env profiles and default_glob
+                match config::load_config_with_scripts() {
+                    Ok(config) => explain_tasks(&config),
+                    Err(e) => {
+                        println!();
+                        println!("  {} Cannot evaluate tasks: {:#}", "✗".red(), e);
+                    }
+                }
+            }
         }
         Err(errors) => {
             print!("{}", ConfigParser::format_validation_errors(&errors));
@@ -107,3 +199,177 @@ pub fn run() -> Result<()> {
 
     Ok(())
 }
+
+/// Explain why each task would or wouldn't run against the current repository state
+fn explain_tasks(config: &Config) {
+    println!();
+    println!("{}", "Explanation:".bold());
+
+    let executor = match TaskExecutor::new(config.settings.clone()) {
+        Ok(executor) => executor,
+        Err(e) => {
+            println!("  {} Cannot evaluate tasks: {}", "✗".red(), e);
+            return;
+        }
+    };
+
+    for (hook_name, hook) in &config.hooks {
+        println!("  {} {}", "→".cyan(), hook_name);
+
+        for task in &hook.tasks {
+            let explanation = executor.explain_task(task);
+            let status = if explanation.will_run {
+                "WILL RUN".green()
+            } else {
+                "SKIPPED".yellow()
+            };
+
+            println!(
+                "      {} {}: {} ({})",
+                "•".dimmed(),
+                task.name,
+                status,
+                explanation.reason
+            );
+        }
+    }
+}
+
+/// Get the npm script or cargo alias a single shell command invokes, if any
+pub fn referenced_script(command: &str) -> Option<ScriptRef<'_>> {
+    let words: Vec<&str> = command.split_whitespace().collect();
+    match words.as_slice() {
+        [runner, "run" | "run-script", name, ..]
+            if matches!(*runner, "npm" | "pnpm" | "yarn" | "bun") =>
+        {
+            Some(ScriptRef::Npm(name))
+        }
+        ["cargo", name, ..] if !name.starts_with(['-', '+']) && !CARGO_BUILTINS.contains(name) => {
+            Some(ScriptRef::Cargo(name))
+        }
+        _ => None,
+    }
+}
+
+/// Warn about tasks whose `npm run` scripts or cargo aliases can't be found
+fn missing_scripts(config: &Config, root: &Path) -> Vec<ValidationError> {
+    let mut warnings = Vec::new();
+
+    for (hook_name, hook) in &config.hooks {
+        for (i, task) in hook.tasks.iter().enumerate() {
+            let dir = root.join(task.cwd.as_deref().unwrap_or("."));
+            let segments = task
+                .platform_run()
+                .commands()
+                .iter()
+                .flat_map(|s| s.split("&&"))
+                .flat_map(|s| s.split("||"))
+                .flat_map(|s| s.split([';', '|']));
+
+            for script in segments.filter_map(referenced_script) {
+                let (exists, suggestion) = match script {
+                    ScriptRef::Npm(name) => (
+                        npm_script_exists(&dir, name),
+                        format!("Add a \"{}\" entry to \"scripts\" in package.json", name),
+                    ),
+                    ScriptRef::Cargo(name) => (
+                        cargo_alias_exists(&dir, name),
+                        format!(
+                            "Define it under [alias] in .cargo/config.toml or install cargo-{}",
+                            name
+                        ),
+                    ),
+                };
+
+                if !exists {
+                    let (kind, name) = match script {
+                        ScriptRef::Npm(name) => ("npm script", name),
+                        ScriptRef::Cargo(name) => ("cargo subcommand", name),
+                    };
+                    warnings.push(ValidationError {
+                        message: format!(
+                            "Task '{}' runs {} '{}' which doesn't exist",
+                            task.name, kind, name
+                        ),
+                        location: Some(format!("hooks.{}.tasks[{}]", hook_name, i)),
+                        suggestion: Some(suggestion),
+                    });
+                }
+            }
+        }
+    }
+
+    warnings
+}
+
+/// Check `package.json` in `dir` for a script named `name`
+fn npm_script_exists(dir: &Path, name: &str) -> bool {
+    std::fs::read_to_string(dir.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .is_some_and(|package| package["scripts"].get(name).is_some())
+}
+
+/// Check for a cargo alias in `.cargo/config(.toml)` up from `dir`, or a `cargo-<name>` binary
+fn cargo_alias_exists(dir: &Path, name: &str) -> bool {
+    let has_alias = dir.ancestors().any(|ancestor| {
+        ["config.toml", "config"].iter().any(|file| {
+            std::fs::read_to_string(ancestor.join(".cargo").join(file))
+                .ok()
+                .and_then(|content| content.parse::<toml::Table>().ok())
+                .and_then(|config| config.get("alias")?.get(name).cloned())
+                .is_some()
+        })
+    });
+
+    has_alias || which::which(format!("cargo-{}", name)).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_referenced_script() {
+        assert_eq!(
+            referenced_script("npm run lint"),
+            Some(ScriptRef::Npm("lint"))
+        );
+        assert_eq!(
+            referenced_script(" pnpm run build -- --prod"),
+            Some(ScriptRef::Npm("build"))
+        );
+        assert_eq!(
+            referenced_script("cargo xtask lint"),
+            Some(ScriptRef::Cargo("xtask"))
+        );
+        assert_eq!(referenced_script("cargo clippy -- -D warnings"), None);
+        assert_eq!(referenced_script("cargo +nightly fmt"), None);
+        assert_eq!(referenced_script("cargo t --workspace"), None);
+        assert_eq!(referenced_script("cargo b --release"), None);
+        assert_eq!(referenced_script("npm test"), None);
+        assert_eq!(referenced_script("eslint --fix"), None);
+    }
+
+    #[test]
+    fn test_missing_scripts_checks_package_json() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("package.json"),
+            r#"{ "name": "app", "scripts": { "lint": "eslint .", "build": "tsc" } }"#,
+        )
+        .unwrap();
+        let config = ConfigParser::parse_toml(
+            r#"
+[hooks]
+pre-commit = ["npm run lint && npm run typecheck", "npm run build"]
+"#,
+        )
+        .unwrap();
+
+        let warnings = missing_scripts(&config, temp_dir.path());
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].message.contains("npm script 'typecheck'"));
+    }
+}
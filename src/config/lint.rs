@@ -0,0 +1,242 @@
+//! Best-practice checks for `fasthooks config lint`, beyond structural validation
+
+use super::{Config, Hook, ValidationError};
+use colored::Colorize;
+
+/// Hooks with more tasks than this are hard to read and slow to run
+pub const MAX_TASKS_PER_HOOK: usize = 10;
+
+/// How much a lint finding matters; findings are listed most severe first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Likely to cost time or surprise someone
+    Warning,
+    /// A matter of style or clarity
+    Info,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Info => write!(f, "info"),
+        }
+    }
+}
+
+/// An advisory finding: a validation message with a severity
+#[derive(Debug)]
+pub struct LintWarning {
+    pub severity: Severity,
+    pub warning: ValidationError,
+}
+
+/// Check the configuration against best practices, most severe findings first
+pub fn lint(config: &Config) -> Vec<LintWarning> {
+    let mut findings = Vec::new();
+
+    let mut hooks: Vec<_> = config.hooks.iter().collect();
+    hooks.sort_by_key(|(name, _)| name.as_str());
+    for (hook_name, hook) in hooks {
+        lint_hook(config, hook_name, hook, &mut findings);
+    }
+
+    findings.sort_by_key(|finding| finding.severity);
+    findings
+}
+
+/// Check a single hook and its tasks
+fn lint_hook(config: &Config, hook_name: &str, hook: &Hook, findings: &mut Vec<LintWarning>) {
+    let mut push = |severity, message, location: String, suggestion: &str| {
+        findings.push(LintWarning {
+            severity,
+            warning: ValidationError {
+                message,
+                location: Some(location),
+                suggestion: Some(suggestion.to_string()),
+            },
+        });
+    };
+
+    if hook.tasks.len() > MAX_TASKS_PER_HOOK {
+        push(
+            Severity::Warning,
+            format!(
+                "Hook '{}' has {} tasks (more than {})",
+                hook_name,
+                hook.tasks.len(),
+                MAX_TASKS_PER_HOOK
+            ),
+            format!("hooks.{}", hook_name),
+            "Move slower checks to pre-push or CI, or group tasks with `stage`",
+        );
+    }
+
+    if hook.git_hook_name(hook_name) == "pre-commit" && config.settings.default_glob.is_none() {
+        for (i, task) in hook.tasks.iter().enumerate() {
+            // `glob = ""` is an explicit opt-out, so only a missing glob is flagged
+            if task.staged && task.glob.is_none() && task.regex.is_none() {
+                push(
+                    Severity::Warning,
+                    format!(
+                        "Task '{}' has no glob, so it runs on every commit",
+                        task.name
+                    ),
+                    format!("hooks.{}.tasks[{}]", hook_name, i),
+                    "Add a `glob` for the files it checks, or `glob = \"\"` if it should always run",
+                );
+            }
+        }
+    }
+
+    if hook.fail_fast.is_none() && hook.tasks.len() > 1 {
+        push(
+            Severity::Info,
+            format!(
+                "Hook '{}' doesn't set fail_fast and inherits settings.fail_fast ({})",
+                hook_name, config.settings.fail_fast
+            ),
+            format!("hooks.{}", hook_name),
+            "Set `fail_fast` on the hook to make the behavior on failure explicit",
+        );
+    }
+
+    if hook.description.is_none() {
+        push(
+            Severity::Info,
+            format!("Hook '{}' has no description", hook_name),
+            format!("hooks.{}", hook_name),
+            "Add a `description` so `fasthooks list` explains what the hook checks",
+        );
+    }
+}
+
+/// Format lint findings for display, with their severity
+pub fn format_lint(findings: &[LintWarning]) -> String {
+    let mut output = String::new();
+
+    output.push_str(&format!(
+        "{} {} finding(s):\n\n",
+        "⚠".yellow().bold(),
+        findings.len()
+    ));
+
+    for (i, finding) in findings.iter().enumerate() {
+        let severity = match finding.severity {
+            Severity::Warning => finding.severity.to_string().yellow().bold(),
+            Severity::Info => finding.severity.to_string().cyan(),
+        };
+        output.push_str(&format!(
+            "{}. [{}] {}\n",
+            i + 1,
+            severity,
+            finding.warning.message
+        ));
+
+        if let Some(loc) = &finding.warning.location {
+            output.push_str(&format!("   {} {}\n", "Location:".dimmed(), loc));
+        }
+
+        if let Some(sug) = &finding.warning.suggestion {
+            output.push_str(&format!("   {} {}\n", "Suggestion:".yellow(), sug));
+        }
+
+        output.push('\n');
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigParser;
+
+    fn messages(config: &str) -> Vec<(Severity, String)> {
+        let config = ConfigParser::parse_toml(config).unwrap();
+        lint(&config)
+            .into_iter()
+            .map(|f| (f.severity, f.warning.message))
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_reports_best_practice_warnings() {
+        let tasks: String = (0..11)
+            .map(|i| {
+                format!(
+                    "[[hooks.pre-push.tasks]]\nname = \"t{}\"\nrun = \"true\"\n",
+                    i
+                )
+            })
+            .collect();
+        let findings = messages(&format!(
+            r#"
+version = "1"
+
+[hooks.pre-commit]
+description = "Fast checks"
+fail_fast = true
+
+[[hooks.pre-commit.tasks]]
+name = "lint"
+run = "eslint {{staged_files}}"
+glob = "*.js"
+
+[[hooks.pre-commit.tasks]]
+name = "test"
+run = "npm test"
+
+[[hooks.pre-commit.tasks]]
+name = "secrets"
+run = "gitleaks protect"
+glob = ""
+
+{}"#,
+            tasks
+        ));
+
+        assert_eq!(
+            findings,
+            vec![
+                (
+                    Severity::Warning,
+                    "Task 'test' has no glob, so it runs on every commit".to_string()
+                ),
+                (
+                    Severity::Warning,
+                    "Hook 'pre-push' has 11 tasks (more than 10)".to_string()
+                ),
+                (
+                    Severity::Info,
+                    "Hook 'pre-push' doesn't set fail_fast and inherits settings.fail_fast (true)"
+                        .to_string()
+                ),
+                (
+                    Severity::Info,
+                    "Hook 'pre-push' has no description".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_default_glob_covers_tasks() {
+        let findings = messages(
+            r#"
+version = "1"
+
+[settings]
+default_glob = "*.rs"
+
+[hooks.pre-commit]
+description = "Rust checks"
+
+[[hooks.pre-commit.tasks]]
+name = "fmt"
+run = "cargo fmt --check"
+"#,
+        );
+        assert!(findings.is_empty(), "{:?}", findings);
+    }
+}
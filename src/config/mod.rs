@@ -2,11 +2,15 @@
 //!
 //! Handles parsing and validation of fasthooks.toml configuration files.
 
+pub mod lint;
 mod parser;
 mod schema;
 
-pub use parser::ConfigParser;
-pub use schema::{Config, Hook, HookType, Settings, Task};
+pub use parser::{ConfigParser, ValidationError};
+pub use schema::{
+    ConcurrencyStrategy, Config, Hook, HookType, NotifyOn, NotifySettings, OutputMode, Settings,
+    Task, TaskCommand, WebhookSettings,
+};
 
 use anyhow::{Context, Result};
 use std::path::{Path, PathBuf};
@@ -15,8 +19,16 @@ use std::path::{Path, PathBuf};
 pub const CONFIG_FILE_NAME: &str = "fasthooks.toml";
 
 /// Alternative configuration file names (for compatibility)
-pub const ALT_CONFIG_FILE_NAMES: &[&str] =
-    &[".fasthooks.toml", "fasthooks.yaml", ".fasthooks.yaml"];
+pub const ALT_CONFIG_FILE_NAMES: &[&str] = &[
+    ".fasthooks.toml",
+    "fasthooks.yaml",
+    ".fasthooks.yaml",
+    "fasthooks.yml",
+    ".fasthooks.yml",
+];
+
+/// Directory holding per-hook scripts (e.g. `.fasthooks/pre-commit.sh`)
+pub const SCRIPTS_DIR: &str = ".fasthooks";
 
 /// Find the configuration file in the repository
 pub fn find_config_file() -> Option<PathBuf> {
@@ -60,6 +72,52 @@ pub fn load_config() -> Result<Config> {
     ConfigParser::parse_file(&config_path)
 }
 
+/// Load configuration, merge in per-hook scripts from `.fasthooks/` and
+/// resolve env profiles.
+///
+/// Kept separate from [`load_config`] so commands that write the config back
+/// don't persist the discovered tasks.
+pub fn load_config_with_scripts() -> Result<Config> {
+    let config_path = find_config_file()
+        .context("No fasthooks.toml found. Run 'fasthooks init' to create one.")?;
+
+    let mut config = ConfigParser::parse_file(&config_path)?;
+    if let Some(root) = config_path.parent() {
+        discover_scripts(&mut config, root);
+    }
+    config.apply_env_profiles()?;
+    config.apply_default_glob();
+    config.resolve_cross_hook_dependencies()?;
+    Ok(config)
+}
+
+/// Append a task for each `.fasthooks/<hook>.sh` script found under `root`,
+/// creating the hook if it isn't configured yet
+pub fn discover_scripts(config: &mut Config, root: &Path) {
+    for hook_type in HookType::all() {
+        let file_name = format!("{}.sh", hook_type.as_str());
+        let script = root.join(SCRIPTS_DIR).join(&file_name);
+        if !script.is_file() {
+            continue;
+        }
+
+        let name = format!("{}/{}", SCRIPTS_DIR, file_name);
+        let hook = config
+            .hooks
+            .entry(hook_type.as_str().to_string())
+            .or_default();
+        if hook.tasks.iter().any(|t| t.name == name) {
+            continue;
+        }
+
+        hook.tasks.push(Task {
+            name,
+            run: format!("sh \"{}\"", script.display()).into(),
+            ..Default::default()
+        });
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -77,10 +135,46 @@ mod tests {
         assert_eq!(found.unwrap(), config_path);
     }
 
+    #[test]
+    fn test_find_config_file_yml() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("fasthooks.yml");
+        fs::write(&config_path, "").unwrap();
+
+        assert_eq!(find_config_file_from(temp_dir.path()), Some(config_path));
+    }
+
     #[test]
     fn test_find_config_file_not_found() {
         let temp_dir = TempDir::new().unwrap();
         let found = find_config_file_from(temp_dir.path());
         assert!(found.is_none());
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_discover_scripts_adds_runnable_task() {
+        let temp_dir = TempDir::new().unwrap();
+        let scripts_dir = temp_dir.path().join(SCRIPTS_DIR);
+        fs::create_dir_all(&scripts_dir).unwrap();
+        fs::write(scripts_dir.join("pre-commit.sh"), "echo from-script\n").unwrap();
+
+        let mut config = ConfigParser::parse_toml("version = \"1\"").unwrap();
+        discover_scripts(&mut config, temp_dir.path());
+        // Discovery is idempotent
+        discover_scripts(&mut config, temp_dir.path());
+
+        let tasks = &config.hooks["pre-commit"].tasks;
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].name, ".fasthooks/pre-commit.sh");
+        assert!(!config.hooks.contains_key("pre-push"));
+
+        let output = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(tasks[0].run.script())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "from-script\n");
+    }
 }
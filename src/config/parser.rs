@@ -1,9 +1,9 @@
 //! Configuration file parser with detailed error reporting
 
-use super::schema::{Config, Hook};
+use super::schema::{Config, Hook, HookType, Task};
 use anyhow::{Context, Result};
 use colored::Colorize;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 
@@ -37,7 +37,27 @@ impl ConfigParser {
         let content = fs::read_to_string(path)
             .with_context(|| format!("Failed to read config file: {}", path.display()))?;
 
-        Self::parse_toml(&content).with_context(|| format!("Failed to parse {}", path.display()))
+        let config = if Self::is_yaml(path) {
+            Self::parse_yaml(&content)
+        } else {
+            Self::parse_toml(&content)
+        };
+        config.with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Whether a config path is read as YAML rather than TOML
+    pub fn is_yaml(path: &Path) -> bool {
+        path.extension()
+            .is_some_and(|ext| ext == "yaml" || ext == "yml")
+    }
+
+    /// Parse YAML content into Config, expanding anchors, aliases and `<<` merge keys
+    pub fn parse_yaml(content: &str) -> Result<Config> {
+        let mut value: serde_yaml_ng::Value =
+            serde_yaml_ng::from_str(content).context("Invalid YAML")?;
+        // serde_yaml_ng expands aliases on its own but leaves `<<` keys in place
+        value.apply_merge().context("Invalid `<<` merge key")?;
+        serde_yaml_ng::from_value(value).context("Invalid configuration")
     }
 
     /// Parse TOML content into Config with detailed error messages
@@ -160,7 +180,7 @@ impl ConfigParser {
         }
 
         if error_lower.contains("unknown field") {
-            return Some("Check the field name for typos. Valid task fields: name, run, glob, staged, cwd, env, allow_failure, if, depends_on".to_string());
+            return Some("Check the field name for typos. Valid task fields: name, run, run_windows, run_unix, fallback, glob, regex, staged, cwd, env, env_profile, files_env, allow_failure, output_file, fail_on_stderr, warn_after_ms, if, depends_on, stdin, inherit_stdin, interactive, file_status, skip_if_only_deletions, per_commit, stage, success_codes, quiet_on_success".to_string());
         }
 
         if error_lower.contains("duplicate key") {
@@ -188,6 +208,18 @@ impl ConfigParser {
         // Validate hooks
         for (hook_name, hook) in &config.hooks {
             Self::validate_hook(hook_name, hook, &mut errors);
+            Self::validate_env_profiles(config, hook_name, hook, &mut errors);
+        }
+        Self::validate_installs_as(config, &mut errors);
+        if let Err(e) = config.clone().resolve_cross_hook_dependencies() {
+            errors.push(ValidationError {
+                message: e.to_string(),
+                location: None,
+                suggestion: Some(
+                    "Reference tasks of other hooks as 'hook:task', e.g. depends_on = [\"pre-commit:build\"]"
+                        .to_string(),
+                ),
+            });
         }
 
         if errors.is_empty() {
@@ -197,9 +229,293 @@ impl ConfigParser {
         }
     }
 
+    /// Collect warnings: likely mistakes that don't make the configuration invalid
+    pub fn warnings(config: &Config) -> Vec<ValidationError> {
+        let mut warnings = Vec::new();
+
+        for (hook_name, hook) in &config.hooks {
+            let parallel = hook.parallel.unwrap_or(config.settings.parallel);
+            Self::warn_hook(hook_name, hook, parallel, &mut warnings);
+        }
+
+        warnings
+    }
+
+    /// Warn about tasks of a single hook that may interfere with each other
+    fn warn_hook(
+        hook_name: &str,
+        hook: &Hook,
+        parallel: bool,
+        warnings: &mut Vec<ValidationError>,
+    ) {
+        if !parallel {
+            return;
+        }
+
+        let mut warned: Vec<(&str, &str)> = Vec::new();
+        let writers: Vec<(usize, &Task)> = hook
+            .tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.glob.is_some() && Self::looks_like_writer(&t.run.script()))
+            .collect();
+
+        for (i, (_, a)) in writers.iter().enumerate() {
+            for (j, b) in &writers[i + 1..] {
+                let ordered = a.depends_on.contains(&b.name) || b.depends_on.contains(&a.name);
+                let (Some(glob_a), Some(glob_b)) = (&a.glob, &b.glob) else {
+                    continue;
+                };
+
+                if !ordered && Self::globs_overlap(glob_a, glob_b) {
+                    warned.push((a.name.as_str(), b.name.as_str()));
+                    warnings.push(ValidationError {
+                        message: format!(
+                            "Tasks '{}' and '{}' both modify files matching overlapping globs and may race in parallel",
+                            a.name, b.name
+                        ),
+                        location: Some(format!("hooks.{}.tasks[{}]", hook_name, j)),
+                        suggestion: Some(format!(
+                            "Add depends_on = [\"{}\"] to '{}' or set parallel = false on the hook",
+                            a.name, b.name
+                        )),
+                    });
+                }
+            }
+        }
+
+        // Generators in the same directory can clobber each other's output
+        let generators: Vec<(usize, &Task)> = hook
+            .tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| Self::looks_like_generator(&t.run.script()))
+            .collect();
+
+        for (i, (_, a)) in generators.iter().enumerate() {
+            for (j, b) in &generators[i + 1..] {
+                let ordered = a.depends_on.contains(&b.name) || b.depends_on.contains(&a.name);
+                let same_cwd = a.cwd.as_deref().unwrap_or(".") == b.cwd.as_deref().unwrap_or(".");
+                if ordered || !same_cwd || warned.contains(&(a.name.as_str(), b.name.as_str())) {
+                    continue;
+                }
+
+                warnings.push(ValidationError {
+                    message: format!(
+                        "Tasks '{}' and '{}' both generate files in '{}' and may clobber each other in parallel",
+                        a.name,
+                        b.name,
+                        a.cwd.as_deref().unwrap_or(".")
+                    ),
+                    location: Some(format!("hooks.{}.tasks[{}]", hook_name, j)),
+                    suggestion: Some(format!(
+                        "Add depends_on = [\"{}\"] to '{}', give them separate cwd values or set parallel = false on the hook",
+                        a.name, b.name
+                    )),
+                });
+            }
+        }
+    }
+
+    /// Heuristic for commands that produce generated files (builds and code generators)
+    fn looks_like_generator(command: &str) -> bool {
+        command.split_whitespace().any(|w| {
+            matches!(w, "build" | "--build" | "--write" | "generate")
+                || w.starts_with("build:")
+                || w.contains("codegen")
+        })
+    }
+
+    /// Heuristic for commands that rewrite files (formatters and auto-fixers)
+    fn looks_like_writer(command: &str) -> bool {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        words
+            .iter()
+            .any(|w| *w == "--write" || w.starts_with("--fix"))
+            || (words.iter().any(|w| w.contains("fmt")) && !words.contains(&"--check"))
+    }
+
+    /// Whether two glob settings can match the same file (ignoring `!` exclusions)
+    fn globs_overlap(a: &str, b: &str) -> bool {
+        let a = Self::include_alternatives(a);
+        let b = Self::include_alternatives(b);
+
+        a.iter().any(|x| {
+            b.iter().any(|y| {
+                let matches = |p: &str, s: &str| glob::Pattern::new(p).is_ok_and(|p| p.matches(s));
+                let extension = |p: &str| {
+                    let name = p.rsplit('/').next().unwrap_or(p);
+                    name.strip_prefix("*.")
+                        .filter(|ext| !ext.contains(['*', '?', '[']))
+                        .map(str::to_string)
+                };
+
+                x == y
+                    || matches(x, y)
+                    || matches(y, x)
+                    || extension(x).is_some_and(|ext| extension(y) == Some(ext))
+            })
+        })
+    }
+
+    /// Split a glob setting into include patterns, expanding `{a,b}` alternatives
+    fn include_alternatives(glob: &str) -> Vec<String> {
+        let mut patterns = Vec::new();
+        let mut current = String::new();
+        let mut depth = 0;
+
+        for c in glob.chars() {
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                ',' | ' ' if depth == 0 => {
+                    patterns.push(std::mem::take(&mut current));
+                    continue;
+                }
+                _ => {}
+            }
+            current.push(c);
+        }
+        patterns.push(current);
+
+        let mut expanded = Vec::new();
+        for pattern in patterns.iter().map(|p| p.trim()) {
+            if pattern.is_empty() || pattern.starts_with('!') {
+                continue;
+            }
+            match (pattern.find('{'), pattern.find('}')) {
+                (Some(open), Some(close)) if open < close => {
+                    for alt in pattern[open + 1..close].split(',') {
+                        expanded.push(format!(
+                            "{}{}{}",
+                            &pattern[..open],
+                            alt.trim(),
+                            &pattern[close + 1..]
+                        ));
+                    }
+                }
+                _ => expanded.push(pattern.to_string()),
+            }
+        }
+
+        expanded
+    }
+
+    /// Check that every `env_profile` reference names a defined profile
+    fn validate_env_profiles(
+        config: &Config,
+        hook_name: &str,
+        hook: &Hook,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        let hook_ref = hook
+            .env_profile
+            .as_ref()
+            .map(|p| (p, format!("hooks.{}.env_profile", hook_name)));
+        let task_refs = hook.tasks.iter().enumerate().filter_map(|(i, t)| {
+            t.env_profile
+                .as_ref()
+                .map(|p| (p, format!("hooks.{}.tasks[{}].env_profile", hook_name, i)))
+        });
+
+        for (profile, location) in hook_ref.into_iter().chain(task_refs) {
+            if !config.env_profiles.contains_key(profile) {
+                errors.push(ValidationError {
+                    message: format!("Unknown env_profile '{}'", profile),
+                    location: Some(location),
+                    suggestion: Some(format!("Define it with [env_profiles.{}]", profile)),
+                });
+            }
+        }
+    }
+
+    /// Check that `installs_as` names a Git hook and no Git hook is claimed twice
+    fn validate_installs_as(config: &Config, errors: &mut Vec<ValidationError>) {
+        let mut hook_names: Vec<&String> = config.hooks.keys().collect();
+        hook_names.sort();
+
+        for hook_name in &hook_names {
+            let Some(target) = &config.hooks[*hook_name].installs_as else {
+                continue;
+            };
+            if HookType::from_str(target).is_none() {
+                errors.push(ValidationError {
+                    message: format!("Unknown Git hook '{}' in installs_as", target),
+                    location: Some(format!("hooks.{}.installs_as", hook_name)),
+                    suggestion: Some(
+                        "Use a Git hook name such as pre-commit, pre-push or commit-msg"
+                            .to_string(),
+                    ),
+                });
+            }
+        }
+
+        let mut claimed: HashMap<HookType, &str> = HashMap::new();
+        for (hook_type, hook_name) in config.git_hooks() {
+            if let Some(other) = claimed.insert(hook_type, hook_name) {
+                errors.push(ValidationError {
+                    message: format!(
+                        "Hooks '{}' and '{}' both install as {}",
+                        other, hook_name, hook_type
+                    ),
+                    location: Some(format!("hooks.{}", hook_name)),
+                    suggestion: Some(
+                        "Merge their tasks or point installs_as at another Git hook".to_string(),
+                    ),
+                });
+            }
+        }
+    }
+
     /// Validate a single hook
     fn validate_hook(hook_name: &str, hook: &Hook, errors: &mut Vec<ValidationError>) {
-        if hook.tasks.is_empty() {
+        let git_hook = hook.git_hook_name(hook_name);
+
+        if let Some(pattern) = &hook.branch_prefix_pattern {
+            let location = Some(format!("hooks.{}.branch_prefix_pattern", hook_name));
+
+            if git_hook != "prepare-commit-msg" {
+                errors.push(ValidationError {
+                    message: format!(
+                        "branch_prefix_pattern is only supported on prepare-commit-msg, not '{}'",
+                        hook_name
+                    ),
+                    location: location.clone(),
+                    suggestion: Some("Move it to [hooks.prepare-commit-msg]".to_string()),
+                });
+            }
+
+            if regex::Regex::new(pattern).is_err() {
+                errors.push(ValidationError {
+                    message: format!(
+                        "Invalid regex pattern '{}' in branch_prefix_pattern",
+                        pattern
+                    ),
+                    location,
+                    suggestion: Some("Check your regex syntax".to_string()),
+                });
+            }
+        }
+
+        if let Some(condition) = &hook.condition {
+            let owner = format!("hook '{}'", hook_name);
+            let location = format!("hooks.{}.if", hook_name);
+            Self::validate_condition(condition, &owner, &location, errors);
+        }
+
+        if hook.append_summary && git_hook != "prepare-commit-msg" {
+            errors.push(ValidationError {
+                message: format!(
+                    "append_summary is only supported on prepare-commit-msg, not '{}'",
+                    hook_name
+                ),
+                location: Some(format!("hooks.{}.append_summary", hook_name)),
+                suggestion: Some("Move it to [hooks.prepare-commit-msg]".to_string()),
+            });
+        }
+
+        // A branch prefix is useful on its own, so such hooks may have no tasks
+        if hook.tasks.is_empty() && hook.branch_prefix_pattern.is_none() {
             errors.push(ValidationError {
                 message: format!("Hook '{}' has no tasks defined", hook_name),
                 location: Some(format!("hooks.{}", hook_name)),
@@ -232,7 +548,7 @@ impl ConfigParser {
             }
 
             // Check for empty run command
-            if task.run.trim().is_empty() {
+            if task.run.is_empty() {
                 errors.push(ValidationError {
                     message: format!("Task '{}' has no command", task.name),
                     location: Some(task_loc.clone()),
@@ -240,9 +556,9 @@ impl ConfigParser {
                 });
             }
 
-            // Validate dependencies exist
+            // Validate dependencies exist (`hook:task` references are checked across hooks)
             for dep in &task.depends_on {
-                if !hook.tasks.iter().any(|t| &t.name == dep) {
+                if !hook.tasks.iter().any(|t| &t.name == dep) && !dep.contains(':') {
                     errors.push(ValidationError {
                         message: format!(
                             "Task '{}' depends on '{}' which doesn't exist",
@@ -257,6 +573,76 @@ impl ConfigParser {
                 }
             }
 
+            // Interactive tasks run before everything else, so they can't wait on other tasks
+            if task.interactive {
+                for dep in &task.depends_on {
+                    if hook.tasks.iter().any(|t| &t.name == dep && !t.interactive) {
+                        errors.push(ValidationError {
+                            message: format!(
+                                "Interactive task '{}' depends on non-interactive task '{}'",
+                                task.name, dep
+                            ),
+                            location: Some(task_loc.clone()),
+                            suggestion: Some(format!(
+                                "Interactive tasks run first; mark '{}' interactive or drop the dependency",
+                                dep
+                            )),
+                        });
+                    }
+                }
+            }
+
+            if task.stdin.is_some() && task.inherit_stdin {
+                errors.push(ValidationError {
+                    message: format!("Task '{}' sets both 'stdin' and 'inherit_stdin'", task.name),
+                    location: Some(task_loc.clone()),
+                    suggestion: Some(
+                        "Use 'stdin' to pipe fixed input, or 'inherit_stdin' for interactive tasks"
+                            .to_string(),
+                    ),
+                });
+            }
+
+            if let Some(status) = &task.file_status {
+                if !matches!(status.as_str(), "added" | "modified" | "renamed") {
+                    errors.push(ValidationError {
+                        message: format!(
+                            "Task '{}' has invalid file_status '{}'",
+                            task.name, status
+                        ),
+                        location: Some(format!("{}.file_status", task_loc)),
+                        suggestion: Some("Use one of: added, modified, renamed".to_string()),
+                    });
+                }
+            }
+
+            if task.success_codes.is_empty() {
+                errors.push(ValidationError {
+                    message: format!("Task '{}' has an empty 'success_codes' list", task.name),
+                    location: Some(format!("{}.success_codes", task_loc)),
+                    suggestion: Some(
+                        "Remove 'success_codes' to accept only exit code 0, or list the accepted codes"
+                            .to_string(),
+                    ),
+                });
+            }
+
+            if let Some(pattern) = &task.regex {
+                if let Err(e) = regex::Regex::new(pattern) {
+                    errors.push(ValidationError {
+                        message: format!(
+                            "Invalid regex '{}' in task '{}': {}",
+                            pattern, task.name, e
+                        ),
+                        location: Some(format!("{}.regex", task_loc)),
+                        suggestion: Some(
+                            "The regex is matched against repository-relative paths, e.g. '^src/.*\\.ts$'"
+                                .to_string(),
+                        ),
+                    });
+                }
+            }
+
             // Validate glob pattern syntax
             if let Some(glob) = &task.glob {
                 Self::validate_glob_pattern(glob, &task.name, &task_loc, errors);
@@ -264,7 +650,8 @@ impl ConfigParser {
 
             // Validate condition syntax
             if let Some(condition) = &task.condition {
-                Self::validate_condition(condition, &task.name, &task_loc, errors);
+                let owner = format!("task '{}'", task.name);
+                Self::validate_condition(condition, &owner, &task_loc, errors);
             }
         }
     }
@@ -298,7 +685,7 @@ impl ConfigParser {
     /// Validate condition syntax
     fn validate_condition(
         condition: &str,
-        task_name: &str,
+        owner: &str,
         location: &str,
         errors: &mut Vec<ValidationError>,
     ) {
@@ -319,24 +706,39 @@ impl ConfigParser {
         if !is_valid {
             errors.push(ValidationError {
                 message: format!(
-                    "Unknown condition format '{}' in task '{}'",
-                    condition, task_name
+                    "Unknown condition format '{}' in {}",
+                    condition, owner
                 ),
                 location: Some(location.to_string()),
                 suggestion: Some(
-                    "Valid conditions: 'branch == main', 'branch != develop', 'env:CI', '!env:CI', 'exists:file.txt'".to_string()
+                    "Valid conditions: 'branch == main', 'branch != develop', 'env:CI', '!env:CI', 'exists:file.txt', 'exists:src/**/*.rs'".to_string()
                 ),
             });
         }
 
+        // Validate glob if using exists: with wildcards
+        if let Some(path) = condition
+            .strip_prefix("exists:")
+            .or_else(|| condition.strip_prefix("!exists:"))
+        {
+            let path = path.trim();
+            if path.contains(['*', '?', '[']) && glob::Pattern::new(path).is_err() {
+                errors.push(ValidationError {
+                    message: format!("Invalid glob pattern '{}' in condition for {}", path, owner),
+                    location: Some(location.to_string()),
+                    suggestion: Some("Valid patterns: src/**/*.rs, *.toml".to_string()),
+                });
+            }
+        }
+
         // Validate regex if using branch =~
         if let Some(pattern) = condition.strip_prefix("branch =~") {
             if regex::Regex::new(pattern.trim()).is_err() {
                 errors.push(ValidationError {
                     message: format!(
-                        "Invalid regex pattern '{}' in condition for task '{}'",
+                        "Invalid regex pattern '{}' in condition for {}",
                         pattern.trim(),
-                        task_name
+                        owner
                     ),
                     location: Some(location.to_string()),
                     suggestion: Some("Check your regex syntax".to_string()),
@@ -372,6 +774,33 @@ impl ConfigParser {
         output
     }
 
+    /// Format validation warnings for display
+    pub fn format_validation_warnings(warnings: &[ValidationError]) -> String {
+        let mut output = String::new();
+
+        output.push_str(&format!(
+            "{} {} warning(s):\n\n",
+            "⚠".yellow().bold(),
+            warnings.len()
+        ));
+
+        for (i, warning) in warnings.iter().enumerate() {
+            output.push_str(&format!("{}. {}\n", i + 1, warning.message.yellow()));
+
+            if let Some(loc) = &warning.location {
+                output.push_str(&format!("   {} {}\n", "Location:".dimmed(), loc));
+            }
+
+            if let Some(sug) = &warning.suggestion {
+                output.push_str(&format!("   {} {}\n", "Suggestion:".yellow(), sug));
+            }
+
+            output.push('\n');
+        }
+
+        output
+    }
+
     /// Serialize Config to TOML string
     pub fn to_toml(config: &Config) -> Result<String> {
         toml::to_string_pretty(config).context("Failed to serialize configuration to TOML")
@@ -507,6 +936,62 @@ if = "branch == main"
         assert_eq!(hook.tasks[0].condition, Some("branch == main".to_string()));
     }
 
+    #[test]
+    fn test_parse_yaml_anchors_expand_in_each_hook() {
+        let content = r#"
+version: "1"
+hooks:
+  pre-commit:
+    tasks:
+      - &lint
+        name: lint
+        run: eslint {staged_files}
+        glob: "*.{js,ts}"
+        env:
+          NODE_ENV: test
+  pre-push:
+    tasks:
+      - *lint
+"#;
+        let config = ConfigParser::parse_yaml(content).unwrap();
+        let commit = &config.hooks["pre-commit"].tasks;
+        let push = &config.hooks["pre-push"].tasks;
+        assert_eq!(commit.len(), 1);
+        assert_eq!(
+            serde_json::to_value(&commit[0]).unwrap(),
+            serde_json::to_value(&push[0]).unwrap()
+        );
+        assert_eq!(push[0].glob.as_deref(), Some("*.{js,ts}"));
+        assert_eq!(push[0].env["NODE_ENV"], "test");
+    }
+
+    #[test]
+    fn test_parse_yaml_merge_keys() {
+        let content = r#"
+version: "1"
+defaults: &defaults
+  glob: "*.rs"
+  cwd: crates/core
+hooks:
+  pre-commit:
+    tasks:
+      - <<: *defaults
+        name: fmt
+        run: cargo fmt --check
+      - <<: *defaults
+        name: test
+        run: cargo test
+        cwd: crates/cli
+"#;
+        let config = ConfigParser::parse_yaml(content).unwrap();
+        let tasks = &config.hooks["pre-commit"].tasks;
+        assert_eq!(tasks[0].glob.as_deref(), Some("*.rs"));
+        assert_eq!(tasks[0].cwd.as_deref(), Some("crates/core"));
+        // Keys written next to `<<` override the merged ones
+        assert_eq!(tasks[1].glob.as_deref(), Some("*.rs"));
+        assert_eq!(tasks[1].cwd.as_deref(), Some("crates/cli"));
+    }
+
     #[test]
     fn test_validate_empty_task_name() {
         let content = r#"
@@ -579,4 +1064,194 @@ run = "npm test"
         // Should contain helpful error information
         assert!(error.contains("Error") || error.contains("expected"));
     }
+
+    #[test]
+    fn test_validate_branch_prefix_pattern_hook_type() {
+        let content = r#"
+version = "1"
+
+[hooks.pre-commit]
+branch_prefix_pattern = "([A-Z]+-\\d+)"
+
+[[hooks.pre-commit.tasks]]
+name = "test"
+run = "npm test"
+"#;
+        let config = ConfigParser::parse_toml(content).unwrap();
+        let errors = ConfigParser::validate(&config).unwrap_err();
+        assert!(errors
+            .iter()
+            .any(|e| e.message.contains("only supported on prepare-commit-msg")));
+    }
+
+    #[test]
+    fn test_validate_installs_as() {
+        let content = r#"
+[hooks.ci]
+installs_as = "pre-push"
+[[hooks.ci.tasks]]
+name = "test"
+run = "cargo test"
+
+[hooks.pre-push]
+[[hooks.pre-push.tasks]]
+name = "lint"
+run = "cargo clippy"
+
+[hooks.nightly]
+installs_as = "nightly-build"
+[[hooks.nightly.tasks]]
+name = "bench"
+run = "cargo bench"
+"#;
+        let config = ConfigParser::parse_toml(content).unwrap();
+        let errors = ConfigParser::validate(&config).unwrap_err();
+        let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Unknown Git hook 'nightly-build' in installs_as",
+                "Hooks 'ci' and 'pre-push' both install as pre-push",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_exists_glob_condition() {
+        let content = r#"
+version = "1"
+
+[hooks.pre-commit]
+[[hooks.pre-commit.tasks]]
+name = "clippy"
+run = "cargo clippy"
+if = "exists:src/**/*.rs"
+
+[[hooks.pre-commit.tasks]]
+name = "broken"
+run = "echo"
+if = "exists:src/[*.rs"
+"#;
+        let config = ConfigParser::parse_toml(content).unwrap();
+        let errors = ConfigParser::validate(&config).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("task 'broken'"));
+    }
+
+    #[test]
+    fn test_warn_generators_sharing_cwd() {
+        let content = r#"
+version = "1"
+
+[hooks.pre-push]
+[[hooks.pre-push.tasks]]
+name = "build"
+run = "npm run build"
+
+[[hooks.pre-push.tasks]]
+name = "codegen"
+run = "graphql-codegen"
+
+[[hooks.pre-push.tasks]]
+name = "docs"
+run = "npm run build"
+cwd = "docs"
+
+[[hooks.pre-push.tasks]]
+name = "lint"
+run = "eslint ."
+
+[[hooks.pre-push.tasks]]
+name = "test"
+run = "npm test"
+"#;
+        let config = ConfigParser::parse_toml(content).unwrap();
+        let warnings = ConfigParser::warnings(&config);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].message.contains("'build' and 'codegen'"));
+        assert!(warnings[0].message.contains("in '.'"));
+
+        // Read-only tasks sharing a cwd are fine
+        let content = r#"
+version = "1"
+
+[hooks.pre-push]
+[[hooks.pre-push.tasks]]
+name = "lint"
+run = "eslint ."
+
+[[hooks.pre-push.tasks]]
+name = "test"
+run = "npm test"
+"#;
+        let config = ConfigParser::parse_toml(content).unwrap();
+        assert!(ConfigParser::warnings(&config).is_empty());
+    }
+
+    #[test]
+    fn test_warn_overlapping_formatters() {
+        let content = r#"
+version = "1"
+
+[hooks.pre-commit]
+[[hooks.pre-commit.tasks]]
+name = "prettier"
+run = "prettier --write {files}"
+glob = "*.{js,ts}"
+
+[[hooks.pre-commit.tasks]]
+name = "eslint"
+run = "eslint --fix {files}"
+glob = "src/**/*.js"
+
+[[hooks.pre-commit.tasks]]
+name = "typecheck"
+run = "tsc --noEmit"
+glob = "*.ts"
+"#;
+        let mut config = ConfigParser::parse_toml(content).unwrap();
+        assert!(ConfigParser::validate(&config).is_ok());
+
+        let warnings = ConfigParser::warnings(&config);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].message.contains("'prettier' and 'eslint'"));
+
+        // Ordering the tasks removes the race
+        let hook = config.hooks.get_mut("pre-commit").unwrap();
+        hook.tasks[1].depends_on = vec!["prettier".to_string()];
+        assert!(ConfigParser::warnings(&config).is_empty());
+    }
+
+    #[test]
+    fn test_no_warning_for_disjoint_or_sequential_formatters() {
+        let content = r#"
+version = "1"
+
+[hooks.pre-commit]
+[[hooks.pre-commit.tasks]]
+name = "rustfmt"
+run = "cargo fmt"
+glob = "*.rs"
+
+[[hooks.pre-commit.tasks]]
+name = "prettier"
+run = "prettier --write"
+glob = "*.md"
+
+[hooks.pre-push]
+parallel = false
+
+[[hooks.pre-push.tasks]]
+name = "prettier"
+run = "prettier --write"
+glob = "*.js"
+
+[[hooks.pre-push.tasks]]
+name = "eslint"
+run = "eslint --fix"
+glob = "*.js"
+"#;
+        let config = ConfigParser::parse_toml(content).unwrap();
+        assert!(ConfigParser::warnings(&config).is_empty());
+    }
 }
@@ -1,7 +1,8 @@
 //! Configuration schema definitions
 
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Main configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,9 +15,21 @@ pub struct Config {
     #[serde(default)]
     pub settings: Settings,
 
-    /// Hook definitions
-    #[serde(default)]
+    /// Hook definitions (a table, or an array of commands as shorthand)
+    #[serde(
+        default,
+        deserialize_with = "deserialize_hooks",
+        serialize_with = "sorted_map"
+    )]
     pub hooks: HashMap<String, Hook>,
+
+    /// Named env tables that hooks and tasks can pull in with `env_profile`
+    #[serde(
+        default,
+        skip_serializing_if = "is_empty_map",
+        serialize_with = "sorted_nested_map"
+    )]
+    pub env_profiles: HashMap<String, HashMap<String, String>>,
 }
 
 impl Default for Config {
@@ -25,10 +38,181 @@ impl Default for Config {
             version: default_version(),
             settings: Settings::default(),
             hooks: HashMap::new(),
+            env_profiles: HashMap::new(),
         }
     }
 }
 
+impl Config {
+    /// Merge each task's env profiles into its `env`.
+    ///
+    /// Precedence, lowest first: the hook's `env_profile`, the task's
+    /// `env_profile`, then the task's own `env` table.
+    pub fn apply_env_profiles(&mut self) -> Result<()> {
+        for (hook_name, hook) in &mut self.hooks {
+            for task in &mut hook.tasks {
+                let mut env = HashMap::new();
+                for profile in [&hook.env_profile, &task.env_profile].into_iter().flatten() {
+                    let values = self.env_profiles.get(profile).with_context(|| {
+                        format!(
+                            "Unknown env_profile '{}' in hook '{}' (task '{}')",
+                            profile, hook_name, task.name
+                        )
+                    })?;
+                    env.extend(values.iter().map(|(k, v)| (k.clone(), v.clone())));
+                }
+                env.extend(std::mem::take(&mut task.env));
+                task.env = env;
+            }
+        }
+        Ok(())
+    }
+
+    /// Give staged tasks without a file filter the `default_glob` setting.
+    ///
+    /// An empty `glob = ""` opts a task out: it is cleared and the task runs
+    /// without filtering files.
+    pub fn apply_default_glob(&mut self) {
+        for task in self.hooks.values_mut().flat_map(|hook| &mut hook.tasks) {
+            match task.glob.as_deref() {
+                Some(glob) if glob.trim().is_empty() => task.glob = None,
+                None if task.staged && task.regex.is_none() => {
+                    task.glob = self.settings.default_glob.clone();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Pull tasks referenced as `hook:task` in `depends_on` into the depending hook.
+    ///
+    /// Each referenced task (and whatever it depends on in turn) is copied in
+    /// under its qualified name, so the dependency sort treats it like any other
+    /// task of the hook. Cycles that cross hooks are reported with their path.
+    pub fn resolve_cross_hook_dependencies(&mut self) -> Result<()> {
+        let mut merged: Vec<(String, Vec<Task>)> = Vec::new();
+        for (hook_name, hook) in &self.hooks {
+            let mut pulled = Vec::new();
+            for task in &hook.tasks {
+                for dep in &task.depends_on {
+                    let (dep_hook, _) = self.resolve_dependency(hook_name, dep);
+                    if dep_hook != hook_name {
+                        let mut stack = vec![(hook_name.as_str(), task.name.as_str())];
+                        self.pull_dependency(hook_name, hook_name, dep, &mut stack, &mut pulled)?;
+                    }
+                }
+            }
+            if !pulled.is_empty() {
+                merged.push((hook_name.clone(), pulled));
+            }
+        }
+
+        // `pre-push:lint` inside pre-push is just `lint`
+        for (hook_name, hook) in &mut self.hooks {
+            let prefix = format!("{}:", hook_name);
+            let names: Vec<String> = hook.tasks.iter().map(|t| t.name.clone()).collect();
+            for dep in hook.tasks.iter_mut().flat_map(|t| &mut t.depends_on) {
+                if names.contains(dep) {
+                    continue;
+                }
+                if let Some(local) = dep.strip_prefix(&prefix) {
+                    *dep = local.to_string();
+                }
+            }
+        }
+        for (hook_name, pulled) in merged {
+            if let Some(hook) = self.hooks.get_mut(&hook_name) {
+                hook.tasks.extend(pulled);
+            }
+        }
+        Ok(())
+    }
+
+    /// Hook and task a `depends_on` entry of a task in `source` refers to.
+    ///
+    /// Tasks of the same hook win, so local task names may contain `:`.
+    fn resolve_dependency<'a>(&self, source: &'a str, dep: &'a str) -> (&'a str, &'a str) {
+        let is_local = self
+            .hooks
+            .get(source)
+            .is_some_and(|hook| hook.tasks.iter().any(|t| t.name == dep));
+        match dep.split_once(':') {
+            Some((hook, task)) if !is_local => (hook, task),
+            _ => (source, dep),
+        }
+    }
+
+    /// Copy the task `dep` (relative to hook `source`) and its dependencies
+    /// into `pulled`, for use by hook `target`
+    fn pull_dependency<'a>(
+        &'a self,
+        target: &str,
+        source: &'a str,
+        dep: &'a str,
+        stack: &mut Vec<(&'a str, &'a str)>,
+        pulled: &mut Vec<Task>,
+    ) -> Result<()> {
+        let (hook_name, task_name) = self.resolve_dependency(source, dep);
+        if stack.contains(&(hook_name, task_name)) {
+            let path: Vec<String> = stack
+                .iter()
+                .chain(std::iter::once(&(hook_name, task_name)))
+                .map(|(hook, task)| format!("{}:{}", hook, task))
+                .collect();
+            anyhow::bail!("Circular dependency across hooks: {}", path.join(" -> "));
+        }
+
+        let task = self
+            .hooks
+            .get(hook_name)
+            .and_then(|hook| hook.tasks.iter().find(|t| t.name == task_name))
+            .with_context(|| {
+                format!(
+                    "Task dependency '{}' not found: hook '{}' has no task '{}'",
+                    dep, hook_name, task_name
+                )
+            })?;
+
+        stack.push((hook_name, task_name));
+        for nested in &task.depends_on {
+            self.pull_dependency(target, hook_name, nested, stack, pulled)?;
+        }
+        stack.pop();
+
+        let qualified = format!("{}:{}", hook_name, task_name);
+        if hook_name != target && !pulled.iter().any(|t| t.name == qualified) {
+            let mut copy = task.clone();
+            copy.name = qualified;
+            copy.depends_on = task
+                .depends_on
+                .iter()
+                .map(|nested| match self.resolve_dependency(hook_name, nested) {
+                    (hook, task) if hook == target => task.to_string(),
+                    (hook, task) => format!("{}:{}", hook, task),
+                })
+                .collect();
+            pulled.push(copy);
+        }
+        Ok(())
+    }
+
+    /// Git hooks to install, with the name of the configured hook each one runs.
+    ///
+    /// Hooks are installed under their own name, or under `installs_as` when set;
+    /// hooks that map to no Git hook can only be run manually.
+    pub fn git_hooks(&self) -> Vec<(HookType, &str)> {
+        let mut hooks: Vec<(HookType, &str)> = self
+            .hooks
+            .iter()
+            .filter_map(|(name, hook)| {
+                HookType::from_str(hook.git_hook_name(name)).map(|t| (t, name.as_str()))
+            })
+            .collect();
+        hooks.sort_by_key(|(_, name)| *name);
+        hooks
+    }
+}
+
 fn default_version() -> String {
     "1".to_string()
 }
@@ -44,6 +228,11 @@ pub struct Settings {
     #[serde(default)]
     pub max_parallel: usize,
 
+    /// How `max_parallel = 0` is resolved: `cpu` (CPU count), `io` (4× CPU count)
+    /// or `fixed` (exactly `max_parallel`)
+    #[serde(default)]
+    pub concurrency_strategy: ConcurrencyStrategy,
+
     /// Show execution time statistics
     #[serde(default = "default_true")]
     pub show_stats: bool,
@@ -56,6 +245,11 @@ pub struct Settings {
     #[serde(default = "default_true")]
     pub fail_fast: bool,
 
+    /// Stop scheduling tasks after this many failures (0 = use `fail_fast`); applies to
+    /// hooks that don't set `fail_fast` themselves
+    #[serde(default)]
+    pub max_failures: usize,
+
     /// Skip hooks if CI environment detected
     #[serde(default)]
     pub skip_ci: bool,
@@ -63,44 +257,482 @@ pub struct Settings {
     /// Colors in output
     #[serde(default = "default_true")]
     pub colors: bool,
+
+    /// Check that every task's command exists before running anything
+    #[serde(default)]
+    pub preflight: bool,
+
+    /// Install a post-checkout hook that re-installs hooks after checkouts
+    #[serde(default)]
+    pub auto_install: bool,
+
+    /// Collapse repeated identical lines in failed task output
+    #[serde(default)]
+    pub dedupe_output: bool,
+
+    /// Show each task name in a color derived from the name, stable across runs
+    #[serde(default)]
+    pub task_colors: bool,
+
+    /// Order in which buffered task output is replayed after the hook finishes
+    #[serde(default)]
+    pub output_mode: OutputMode,
+
+    /// Discard the captured output of tasks that succeed (tasks can override it)
+    #[serde(default)]
+    pub quiet_on_success: bool,
+
+    /// Fail hooks when the configuration can't be loaded (otherwise warn and allow)
+    #[serde(default = "default_true")]
+    pub stop_on_config_error: bool,
+
+    /// Fail a hook when every task was skipped or filtered out (also `--require-tasks`)
+    #[serde(default)]
+    pub require_tasks: bool,
+
+    /// After a failed hook, print how to bypass it (e.g. `git commit --no-verify`)
+    #[serde(default)]
+    pub show_bypass_hint: bool,
+
+    /// Re-run a failed hook up to this many extra times
+    #[serde(default)]
+    pub hook_retries: u32,
+
+    /// Command to run after a hook finishes (e.g. desktop notification)
+    #[serde(default)]
+    pub notify: Option<NotifySettings>,
+
+    /// Webhook that receives the JSON result of every hook run
+    #[serde(default)]
+    pub webhook: Option<WebhookSettings>,
+
+    /// Refuse to run hooks as root unless `--allow-root` is passed
+    #[serde(default)]
+    pub forbid_root: bool,
+
+    /// Match task globs without regard to case (for case-insensitive filesystems)
+    #[serde(default)]
+    pub case_insensitive_globs: bool,
+
+    /// Branch globs (e.g. `main`, `release/*`) on which every task runs, ignoring `if` conditions
+    #[serde(default)]
+    pub protected_branches: Vec<String>,
+
+    /// Glob for staged tasks that set neither `glob` nor `regex` (`glob = ""` opts out)
+    #[serde(default)]
+    pub default_glob: Option<String>,
+
+    /// Fail pre-commit when staged files contain merge conflict markers
+    #[serde(default)]
+    pub check_conflict_markers: bool,
+
+    /// Fail pre-commit when a staged file is larger than this many bytes
+    #[serde(default)]
+    pub max_file_size_bytes: Option<u64>,
 }
 
 fn default_true() -> bool {
     true
 }
 
+fn default_success_codes() -> Vec<i32> {
+    vec![0]
+}
+
+fn default_webhook_timeout_ms() -> u64 {
+    5000
+}
+
+// Config files are written without default values to keep them minimal; the
+// `omitted_defaults` tables below let `config get`/`set` still address those keys
+
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+fn is_default_success_codes(codes: &[i32]) -> bool {
+    codes == [0]
+}
+
+fn is_empty_map<K, V>(map: &HashMap<K, V>) -> bool {
+    map.is_empty()
+}
+
+fn is_empty_vec<T>(vec: &[T]) -> bool {
+    vec.is_empty()
+}
+
+/// Serialize a map with sorted keys so rewritten configs are stable
+fn sorted_map<S, V>(map: &HashMap<String, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    V: Serialize,
+{
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
+}
+
+/// [`sorted_map`] for maps of maps, sorting both levels
+fn sorted_nested_map<S>(
+    map: &HashMap<String, HashMap<String, String>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    map.iter()
+        .map(|(key, inner)| (key, inner.iter().collect::<BTreeMap<_, _>>()))
+        .collect::<BTreeMap<_, _>>()
+        .serialize(serializer)
+}
+
 impl Default for Settings {
     fn default() -> Self {
         Self {
             parallel: true,
             max_parallel: 0,
+            concurrency_strategy: ConcurrencyStrategy::default(),
             show_stats: true,
             show_carbon_savings: true,
             fail_fast: true,
+            max_failures: 0,
             skip_ci: false,
             colors: true,
+            preflight: false,
+            auto_install: false,
+            dedupe_output: false,
+            task_colors: false,
+            output_mode: OutputMode::default(),
+            quiet_on_success: false,
+            stop_on_config_error: true,
+            require_tasks: false,
+            show_bypass_hint: false,
+            hook_retries: 0,
+            notify: None,
+            webhook: None,
+            forbid_root: false,
+            case_insensitive_globs: false,
+            protected_branches: Vec::new(),
+            default_glob: None,
+            check_conflict_markers: false,
+            max_file_size_bytes: None,
+        }
+    }
+}
+
+/// Notification command run after a hook finishes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotifySettings {
+    /// When to notify
+    #[serde(default)]
+    pub on: NotifyOn,
+
+    /// Command template; `{status}` and `{duration}` are substituted
+    pub command: String,
+}
+
+/// Which hook outcomes trigger a notification
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyOn {
+    #[default]
+    Always,
+    Failure,
+    Success,
+}
+
+/// How the number of concurrent tasks is chosen
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConcurrencyStrategy {
+    /// One task per CPU core, for CPU-bound tools
+    #[default]
+    Cpu,
+    /// Several tasks per CPU core, for tools that mostly wait on the network or disk
+    Io,
+    /// Exactly `max_parallel` tasks
+    Fixed,
+}
+
+/// How the captured output of tasks is replayed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputMode {
+    /// One task at a time, in config order, once all tasks have finished
+    #[default]
+    BufferedOrdered,
+    /// One task at a time, in the order the tasks were run
+    ExecutionOrder,
+}
+
+/// Webhook that hook results are POSTed to as JSON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookSettings {
+    /// URL to POST to
+    pub url: String,
+
+    /// Which outcomes to post
+    #[serde(default)]
+    pub on: NotifyOn,
+
+    /// Give up on the request after this many milliseconds
+    #[serde(default = "default_webhook_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+/// Deserialize hooks, accepting `pre-commit = ["cargo fmt", "cargo clippy"]`
+/// as shorthand for one task per command (named after the command)
+fn deserialize_hooks<'de, D>(deserializer: D) -> Result<HashMap<String, Hook>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let hooks: HashMap<String, HookValue> = HashMap::deserialize(deserializer)?;
+    Ok(hooks
+        .into_iter()
+        .map(|(name, value)| (name, value.0))
+        .collect())
+}
+
+/// A task's command: a single command, or an array of commands run in sequence
+/// in one shell that stops at the first failure
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum TaskCommand {
+    Single(String),
+    Sequence(Vec<String>),
+}
+
+impl TaskCommand {
+    /// The commands in the order they run
+    pub fn commands(&self) -> &[String] {
+        match self {
+            TaskCommand::Single(command) => std::slice::from_ref(command),
+            TaskCommand::Sequence(commands) => commands,
+        }
+    }
+
+    /// Whether there is nothing to run, or a command in the sequence is blank
+    pub fn is_empty(&self) -> bool {
+        let commands = self.commands();
+        commands.is_empty() || commands.iter().any(|c| c.trim().is_empty())
+    }
+
+    /// The shell script running the commands in sequence
+    pub fn script(&self) -> String {
+        Self::join(self.commands())
+    }
+
+    /// Chain commands with `&&`, grouping each one so that an `||` or a `#` comment
+    /// in one command can't swallow the commands after it. A single command is
+    /// returned unchanged.
+    pub fn join(commands: &[String]) -> String {
+        if let [command] = commands {
+            return command.clone();
+        }
+        commands
+            .iter()
+            .map(|command| {
+                if cfg!(windows) {
+                    format!("({})", command)
+                } else {
+                    format!("{{ {}\n}}", command)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" && ")
+    }
+}
+
+impl Default for TaskCommand {
+    fn default() -> Self {
+        TaskCommand::Single(String::new())
+    }
+}
+
+impl From<String> for TaskCommand {
+    fn from(command: String) -> Self {
+        TaskCommand::Single(command)
+    }
+}
+
+impl From<&str> for TaskCommand {
+    fn from(command: &str) -> Self {
+        TaskCommand::Single(command.to_string())
+    }
+}
+
+// Implemented with a visitor rather than `#[serde(untagged)]` so the error names
+// what is expected instead of "did not match any variant"
+impl<'de> Deserialize<'de> for TaskCommand {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct CommandVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for CommandVisitor {
+            type Value = TaskCommand;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a command or an array of commands")
+            }
+
+            fn visit_str<E>(self, command: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(TaskCommand::Single(command.to_string()))
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let mut commands = Vec::new();
+                while let Some(command) = seq.next_element::<String>()? {
+                    commands.push(command);
+                }
+                Ok(TaskCommand::Sequence(commands))
+            }
+        }
+
+        deserializer.deserialize_any(CommandVisitor)
+    }
+}
+
+/// A hook given either as a table or as an array of commands.
+///
+/// Implemented with a visitor rather than `#[serde(untagged)]` so errors
+/// inside a full hook table keep their field-level messages.
+struct HookValue(Hook);
+
+impl<'de> Deserialize<'de> for HookValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct HookVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for HookVisitor {
+            type Value = HookValue;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a hook table or an array of commands")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let mut tasks = Vec::new();
+                while let Some(command) = seq.next_element::<String>()? {
+                    tasks.push(Task {
+                        name: command.clone(),
+                        run: command.into(),
+                        ..Default::default()
+                    });
+                }
+                Ok(HookValue(Hook {
+                    tasks,
+                    ..Default::default()
+                }))
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                Hook::deserialize(serde::de::value::MapAccessDeserializer::new(map)).map(HookValue)
+            }
         }
+
+        deserializer.deserialize_any(HookVisitor)
     }
 }
 
 /// A Git hook definition
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Hook {
+    /// Human-readable description shown by `list` and `validate`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
     /// Tasks to run for this hook
     #[serde(default)]
     pub tasks: Vec<Task>,
 
     /// Run tasks in parallel (overrides global setting)
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub parallel: Option<bool>,
 
     /// Fail fast for this hook (overrides global setting)
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub fail_fast: Option<bool>,
 
+    /// Condition to run this hook at all, in the same syntax as task `if`
+    #[serde(rename = "if", default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+
     /// Skip this hook in CI
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub skip_ci: Option<bool>,
+
+    /// Regex matched against the current branch (prepare-commit-msg only);
+    /// the first capture group (or whole match) is prepended to the commit message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch_prefix_pattern: Option<String>,
+
+    /// Append the run summary as a `#` comment block to the commit message (prepare-commit-msg only)
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub append_summary: bool,
+
+    /// Warn when the hook's wall time exceeds this many milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_budget_ms: Option<u64>,
+
+    /// Fail the hook instead of warning when the time budget is exceeded
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub strict_time_budget: bool,
+
+    /// Env profile (from `[env_profiles.<name>]`) applied to every task of the hook
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_profile: Option<String>,
+
+    /// Git hook to install this hook as, for custom-named hooks like `ci`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installs_as: Option<String>,
+}
+
+impl Config {
+    /// Values of the fields left out of serialized configs when they're at their default
+    pub fn omitted_defaults() -> toml::Table {
+        let mut table = toml::Table::new();
+        table.insert("env_profiles".to_string(), toml::Table::new().into());
+        table
+    }
+}
+
+impl Hook {
+    /// Values of the fields left out of serialized configs when they're at their default
+    pub fn omitted_defaults() -> toml::Table {
+        let hook = Hook::default();
+        let mut table = toml::Table::new();
+        for (key, value) in [
+            ("append_summary", hook.append_summary),
+            ("strict_time_budget", hook.strict_time_budget),
+        ] {
+            table.insert(key.to_string(), value.into());
+        }
+        table
+    }
+
+    /// Name of the Git hook this configured hook (named `name`) stands for
+    pub fn git_hook_name<'a>(&'a self, name: &'a str) -> &'a str {
+        self.installs_as.as_deref().unwrap_or(name)
+    }
 }
 
 /// A task within a hook
@@ -109,37 +741,205 @@ pub struct Task {
     /// Task name (for display)
     pub name: String,
 
-    /// Command to execute
-    pub run: String,
+    /// Command to execute; an array of commands runs them in sequence, stopping at the first failure
+    pub run: TaskCommand,
+
+    /// Command to execute on Windows instead of `run` (also accepts an array)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_windows: Option<TaskCommand>,
+
+    /// Command to execute on Unix-like systems instead of `run` (also accepts an array)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_unix: Option<TaskCommand>,
+
+    /// Command to run instead when the main command's program isn't installed
+    /// (an empty string skips the task)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<String>,
 
     /// Glob patterns for files to match (lint-staged style)
     /// Supports negation with ! prefix (e.g., "!*.test.js")
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub glob: Option<String>,
 
+    /// Regex matched against the repository-relative path (combined with `glob` if both are set)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regex: Option<String>,
+
     /// Only run on staged files
-    #[serde(default = "default_true")]
+    #[serde(default = "default_true", skip_serializing_if = "is_true")]
     pub staged: bool,
 
     /// Working directory for the command
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub cwd: Option<String>,
 
     /// Environment variables
-    #[serde(default)]
+    #[serde(
+        default,
+        skip_serializing_if = "is_empty_map",
+        serialize_with = "sorted_map"
+    )]
     pub env: HashMap<String, String>,
 
+    /// Env profile (from `[env_profiles.<name>]`) merged under `env`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_profile: Option<String>,
+
+    /// Env var that receives the matched files, one per line; files are then
+    /// only put on the command line where `{files}` appears
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub files_env: Option<String>,
+
     /// Continue even if this task fails
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub allow_failure: bool,
 
+    /// File that receives a copy of the command's stdout (relative to `cwd`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_file: Option<String>,
+
+    /// Fail the task when it exits successfully but writes to stderr
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub fail_on_stderr: bool,
+
+    /// Print a "still running" note every this many milliseconds while the task runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warn_after_ms: Option<u64>,
+
     /// Condition to run this task (e.g., "branch == main", "branch != main", "env:CI")
-    #[serde(rename = "if", default)]
+    #[serde(rename = "if", default, skip_serializing_if = "Option::is_none")]
     pub condition: Option<String>,
 
     /// Task dependencies - names of tasks that must run before this one
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_empty_vec")]
     pub depends_on: Vec<String>,
+
+    /// Content piped to the command's standard input
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stdin: Option<String>,
+
+    /// Inherit the terminal's standard input (forces sequential execution)
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub inherit_stdin: bool,
+
+    /// Run with the terminal's stdin/stdout/stderr, one at a time before all other tasks
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub interactive: bool,
+
+    /// Only match staged files with this status: "added", "modified" or "renamed"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_status: Option<String>,
+
+    /// Skip the task when every file it matched is a staged deletion
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub skip_if_only_deletions: bool,
+
+    /// In `pre-rebase`, run once per commit being rebased with `{sha}` set to that commit
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub per_commit: bool,
+
+    /// Stage this task is grouped under in the run output (e.g. "Lint")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stage: Option<String>,
+
+    /// Exit codes that count as success (e.g. `[0, 1]` for grep-like tools)
+    #[serde(
+        default = "default_success_codes",
+        skip_serializing_if = "is_default_success_codes"
+    )]
+    pub success_codes: Vec<i32>,
+
+    /// Discard captured output when the task succeeds (overrides the global setting)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quiet_on_success: Option<bool>,
+}
+
+impl Default for Task {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            run: TaskCommand::default(),
+            run_windows: None,
+            run_unix: None,
+            fallback: None,
+            glob: None,
+            regex: None,
+            staged: true,
+            cwd: None,
+            env: HashMap::new(),
+            env_profile: None,
+            files_env: None,
+            allow_failure: false,
+            output_file: None,
+            fail_on_stderr: false,
+            warn_after_ms: None,
+            condition: None,
+            depends_on: Vec::new(),
+            stdin: None,
+            inherit_stdin: false,
+            interactive: false,
+            file_status: None,
+            skip_if_only_deletions: false,
+            per_commit: false,
+            stage: None,
+            success_codes: default_success_codes(),
+            quiet_on_success: None,
+        }
+    }
+}
+
+impl Task {
+    /// Whether the task only runs on files matching a `glob` and/or `regex`
+    pub fn filters_files(&self) -> bool {
+        self.glob.is_some() || self.regex.is_some()
+    }
+
+    /// Human-readable description of the file filter, e.g. `*.rs` or `/^src\//`
+    pub fn file_filter(&self) -> Option<String> {
+        match (&self.glob, &self.regex) {
+            (Some(glob), Some(regex)) => Some(format!("{} and /{}/", glob, regex)),
+            (Some(glob), None) => Some(glob.clone()),
+            (None, Some(regex)) => Some(format!("/{}/", regex)),
+            (None, None) => None,
+        }
+    }
+
+    /// Values of the fields left out of serialized configs when they're at their default
+    pub fn omitted_defaults() -> toml::Table {
+        let task = Task::default();
+        let mut table = toml::Table::new();
+        for (key, value) in [
+            ("staged", task.staged),
+            ("allow_failure", task.allow_failure),
+            ("fail_on_stderr", task.fail_on_stderr),
+            ("inherit_stdin", task.inherit_stdin),
+            ("interactive", task.interactive),
+            ("skip_if_only_deletions", task.skip_if_only_deletions),
+            ("per_commit", task.per_commit),
+        ] {
+            table.insert(key.to_string(), value.into());
+        }
+        table.insert("env".to_string(), toml::Table::new().into());
+        table.insert("depends_on".to_string(), toml::Value::Array(Vec::new()));
+        let codes: Vec<toml::Value> = task
+            .success_codes
+            .iter()
+            .map(|&code| i64::from(code).into())
+            .collect();
+        table.insert("success_codes".to_string(), codes.into());
+        table
+    }
+
+    /// The command for the current OS, falling back to `run`
+    pub fn platform_run(&self) -> &TaskCommand {
+        let specific = if cfg!(windows) {
+            self.run_windows.as_ref()
+        } else {
+            self.run_unix.as_ref()
+        };
+        specific.unwrap_or(&self.run)
+    }
 }
 
 /// Supported Git hook types
@@ -268,4 +1068,271 @@ mod tests {
         let task: Task = toml::from_str(toml).unwrap();
         assert_eq!(task.condition, Some("branch == main".to_string()));
     }
+
+    #[test]
+    fn test_apply_env_profiles_precedence() {
+        let toml = r#"
+            [env_profiles.base]
+            NODE_ENV = "development"
+            LOG = "info"
+
+            [env_profiles.ci]
+            NODE_ENV = "test"
+
+            [hooks.pre-commit]
+            env_profile = "base"
+
+            [[hooks.pre-commit.tasks]]
+            name = "test"
+            run = "npm test"
+            env_profile = "ci"
+            env = { LOG = "debug" }
+
+            [[hooks.pre-commit.tasks]]
+            name = "lint"
+            run = "npm run lint"
+        "#;
+        let mut config: Config = toml::from_str(toml).unwrap();
+        config.apply_env_profiles().unwrap();
+
+        let tasks = &config.hooks["pre-commit"].tasks;
+        assert_eq!(tasks[0].env["NODE_ENV"], "test");
+        assert_eq!(tasks[0].env["LOG"], "debug");
+        assert_eq!(tasks[1].env["NODE_ENV"], "development");
+        assert_eq!(tasks[1].env["LOG"], "info");
+
+        config.hooks.get_mut("pre-commit").unwrap().env_profile = Some("missing".to_string());
+        assert!(config.apply_env_profiles().is_err());
+    }
+
+    #[test]
+    fn test_cross_hook_dependencies() {
+        let toml = r#"
+            [[hooks.pre-commit.tasks]]
+            name = "deps"
+            run = "npm ci"
+
+            [[hooks.pre-commit.tasks]]
+            name = "build"
+            run = "npm run build"
+            depends_on = ["deps"]
+
+            [[hooks.pre-push.tasks]]
+            name = "e2e"
+            run = "npm run e2e"
+            depends_on = ["pre-commit:build"]
+        "#;
+        let mut config: Config = toml::from_str(toml).unwrap();
+        config.resolve_cross_hook_dependencies().unwrap();
+
+        let pre_push = &config.hooks["pre-push"];
+        let sorted = crate::runner::TaskExecutor::sort_tasks_by_dependencies(&pre_push.tasks)
+            .unwrap()
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(sorted, vec!["pre-commit:deps", "pre-commit:build", "e2e"]);
+        let build = pre_push.tasks.iter().find(|t| t.name == "pre-commit:build");
+        assert_eq!(build.unwrap().depends_on, vec!["pre-commit:deps"]);
+        // The source hook is left alone
+        assert_eq!(config.hooks["pre-commit"].tasks.len(), 2);
+    }
+
+    #[test]
+    fn test_cross_hook_dependency_cycle() {
+        let toml = r#"
+            [[hooks.pre-commit.tasks]]
+            name = "build"
+            run = "npm run build"
+            depends_on = ["pre-push:e2e"]
+
+            [[hooks.pre-push.tasks]]
+            name = "e2e"
+            run = "npm run e2e"
+            depends_on = ["pre-commit:build"]
+        "#;
+        let mut config: Config = toml::from_str(toml).unwrap();
+        let err = config.resolve_cross_hook_dependencies().unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Circular dependency across hooks:"));
+
+        let mut config: Config =
+            toml::from_str(&toml.replace("pre-commit:build", "pre-commit:missing")).unwrap();
+        let err = config.resolve_cross_hook_dependencies().unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("hook 'pre-commit' has no task 'missing'"));
+    }
+
+    #[test]
+    fn test_run_accepts_string_or_array() {
+        let toml = r#"
+            [[hooks.pre-commit.tasks]]
+            name = "single"
+            run = "cargo test"
+
+            [[hooks.pre-commit.tasks]]
+            name = "sequence"
+            run = ["npm ci", "npm run lint"]
+            run_windows = ["npm ci", "npm run lint:win"]
+        "#;
+        let config: Config = toml::from_str(toml).unwrap();
+        let tasks = &config.hooks["pre-commit"].tasks;
+        assert_eq!(tasks[0].run, TaskCommand::from("cargo test"));
+        assert_eq!(
+            tasks[1].run.commands(),
+            ["npm ci".to_string(), "npm run lint".to_string()]
+        );
+        assert_eq!(
+            tasks[1].run_windows.as_ref().unwrap().commands()[1],
+            "npm run lint:win"
+        );
+
+        // Rewriting the config keeps the array
+        let rewritten = toml::to_string(&config).unwrap();
+        assert!(rewritten.contains(r#"run = ["npm ci", "npm run lint"]"#));
+
+        let err = toml::from_str::<Config>(
+            r#"
+            [[hooks.pre-commit.tasks]]
+            name = "bad"
+            run = 3
+        "#,
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("a command or an array of commands"));
+    }
+
+    #[test]
+    fn test_apply_default_glob() {
+        let toml = r#"
+            [settings]
+            default_glob = "*.{ts,tsx}"
+
+            [[hooks.pre-commit.tasks]]
+            name = "lint"
+            run = "eslint"
+
+            [[hooks.pre-commit.tasks]]
+            name = "fmt"
+            run = "cargo fmt"
+            glob = "*.rs"
+
+            [[hooks.pre-commit.tasks]]
+            name = "audit"
+            run = "npm audit"
+            glob = ""
+
+            [[hooks.pre-commit.tasks]]
+            name = "docs"
+            run = "mdbook build"
+            regex = "^docs/"
+
+            [[hooks.pre-commit.tasks]]
+            name = "test"
+            run = "npm test"
+            staged = false
+        "#;
+        let mut config: Config = toml::from_str(toml).unwrap();
+        config.apply_default_glob();
+
+        let globs: Vec<Option<&str>> = config.hooks["pre-commit"]
+            .tasks
+            .iter()
+            .map(|t| t.glob.as_deref())
+            .collect();
+        assert_eq!(
+            globs,
+            vec![Some("*.{ts,tsx}"), Some("*.rs"), None, None, None]
+        );
+        // The opted-out task no longer filters files, so it always runs
+        assert!(!config.hooks["pre-commit"].tasks[2].filters_files());
+    }
+
+    #[test]
+    fn test_hook_shorthand_matches_full_form() {
+        let shorthand: Config = toml::from_str(
+            r#"
+            [hooks]
+            pre-commit = ["cargo fmt", "cargo clippy"]
+        "#,
+        )
+        .unwrap();
+        let full: Config = toml::from_str(
+            r#"
+            [[hooks.pre-commit.tasks]]
+            name = "cargo fmt"
+            run = "cargo fmt"
+
+            [[hooks.pre-commit.tasks]]
+            name = "cargo clippy"
+            run = "cargo clippy"
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            toml::to_string(&shorthand).unwrap(),
+            toml::to_string(&full).unwrap()
+        );
+        assert_eq!(
+            shorthand.hooks["pre-commit"].tasks[1].run.script(),
+            "cargo clippy"
+        );
+    }
+
+    #[test]
+    fn test_serialization_is_minimal_and_ordered() {
+        let toml_in = r#"
+            [hooks.pre-push]
+            [[hooks.pre-push.tasks]]
+            name = "test"
+            run = "cargo test"
+
+            [hooks.pre-commit]
+            parallel = false
+
+            [[hooks.pre-commit.tasks]]
+            run = "cargo fmt --check"
+            name = "fmt"
+            allow_failure = false
+            staged = true
+            success_codes = [0]
+            env = { B = "2", A = "1" }
+        "#;
+        let config: Config = toml::from_str(toml_in).unwrap();
+        let hooks_only = |config: &Config| {
+            let out = toml::to_string(config).unwrap();
+            out[out.find("[hooks").unwrap()..].to_string()
+        };
+
+        let expected = r#"[hooks.pre-commit]
+parallel = false
+
+[[hooks.pre-commit.tasks]]
+name = "fmt"
+run = "cargo fmt --check"
+
+[hooks.pre-commit.tasks.env]
+A = "1"
+B = "2"
+
+[[hooks.pre-push.tasks]]
+name = "test"
+run = "cargo test"
+"#;
+        assert_eq!(hooks_only(&config), expected);
+
+        // Round-trips to the same text
+        let reparsed: Config = toml::from_str(&toml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(hooks_only(&reparsed), expected);
+
+        // Omitted fields are listed with their defaults
+        let defaults = Task::omitted_defaults();
+        assert_eq!(defaults["allow_failure"], toml::Value::Boolean(false));
+        assert_eq!(defaults["success_codes"].to_string(), "[0]");
+    }
 }
@@ -7,6 +7,46 @@ use anyhow::{Context, Result};
 use git2::Repository;
 use std::path::PathBuf;
 
+/// How a staged file differs from HEAD
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileStatus {
+    Added,
+    Modified,
+    Renamed,
+    Deleted,
+    Other,
+}
+
+impl FileStatus {
+    /// Parse a status name as used in a task's `file_status`
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "added" => Some(Self::Added),
+            "modified" => Some(Self::Modified),
+            "renamed" => Some(Self::Renamed),
+            "deleted" => Some(Self::Deleted),
+            _ => None,
+        }
+    }
+
+    fn from_delta(delta: git2::Delta) -> Self {
+        match delta {
+            git2::Delta::Added | git2::Delta::Copied => Self::Added,
+            git2::Delta::Modified | git2::Delta::Typechange => Self::Modified,
+            git2::Delta::Renamed => Self::Renamed,
+            git2::Delta::Deleted => Self::Deleted,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// A staged file and its status
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StagedFile {
+    pub path: PathBuf,
+    pub status: FileStatus,
+}
+
 /// Wrapper around git2::Repository for common operations
 #[allow(dead_code)]
 pub struct GitRepository {
@@ -19,16 +59,30 @@ impl GitRepository {
     pub fn discover() -> Result<Self> {
         let repo = Repository::discover(".")
             .context("Not a git repository (or any of the parent directories)")?;
-        Ok(Self { repo })
+        Self::with_index_file(repo, std::env::var_os("GIT_INDEX_FILE"))
     }
 
-    /// Open a Git repository at a specific path
+    /// Open a Git repository at a specific path, using its own index
+    /// (`GIT_INDEX_FILE` belongs to the repository Git is running a hook for)
     pub fn open(path: &std::path::Path) -> Result<Self> {
         let repo = Repository::open(path)
             .with_context(|| format!("Failed to open repository at {}", path.display()))?;
         Ok(Self { repo })
     }
 
+    /// Use `index_file` instead of the default index when set, as Git does for
+    /// `GIT_INDEX_FILE` (e.g. the temporary index of `git commit <paths>`)
+    fn with_index_file(repo: Repository, index_file: Option<std::ffi::OsString>) -> Result<Self> {
+        if let Some(path) = index_file.filter(|p| !p.is_empty()) {
+            let path = PathBuf::from(path);
+            let mut index = git2::Index::open(&path)
+                .with_context(|| format!("Failed to open GIT_INDEX_FILE {}", path.display()))?;
+            repo.set_index(&mut index)
+                .context("Failed to use GIT_INDEX_FILE")?;
+        }
+        Ok(Self { repo })
+    }
+
     /// Get the path to the .git directory
     pub fn git_dir(&self) -> PathBuf {
         self.repo.path().to_path_buf()
@@ -39,6 +93,11 @@ impl GitRepository {
         self.repo.path().join("hooks")
     }
 
+    /// Get the directory where FastHooks keeps its per-repository state
+    pub fn state_dir(&self) -> PathBuf {
+        self.repo.path().join("fasthooks")
+    }
+
     /// Get the repository root (working directory)
     pub fn workdir(&self) -> Option<PathBuf> {
         self.repo.workdir().map(|p| p.to_path_buf())
@@ -46,19 +105,40 @@ impl GitRepository {
 
     /// Get list of staged files
     pub fn staged_files(&self) -> Result<Vec<PathBuf>> {
+        Ok(self
+            .staged_entries()?
+            .into_iter()
+            .map(|entry| entry.path)
+            .collect())
+    }
+
+    /// Get staged files together with their status (renames are detected).
+    ///
+    /// Submodule gitlinks (mode `160000`) are skipped: they point at commits
+    /// in another repository, not at files tasks could lint.
+    pub fn staged_entries(&self) -> Result<Vec<StagedFile>> {
         let mut files = Vec::new();
         let head = self.repo.head().ok();
         let head_tree = head.as_ref().and_then(|h| h.peel_to_tree().ok());
 
-        let diff = self
+        let mut diff = self
             .repo
             .diff_tree_to_index(head_tree.as_ref(), None, None)
             .context("Failed to get staged changes")?;
+        diff.find_similar(Some(git2::DiffFindOptions::new().renames(true)))
+            .context("Failed to detect renamed files")?;
 
         diff.foreach(
             &mut |delta, _| {
+                let is_gitlink = |file: git2::DiffFile| file.mode() == git2::FileMode::Commit;
+                if is_gitlink(delta.old_file()) || is_gitlink(delta.new_file()) {
+                    return true;
+                }
                 if let Some(path) = delta.new_file().path() {
-                    files.push(path.to_path_buf());
+                    files.push(StagedFile {
+                        path: path.to_path_buf(),
+                        status: FileStatus::from_delta(delta.status()),
+                    });
                 }
                 true
             },
@@ -71,6 +151,73 @@ impl GitRepository {
         Ok(files)
     }
 
+    /// Read the staged (index) content of a file, or `None` if it isn't in the index
+    pub fn staged_content(&self, path: &std::path::Path) -> Result<Option<Vec<u8>>> {
+        let index = self.repo.index().context("Failed to read the index")?;
+        let Some(entry) = index.get_path(path, 0) else {
+            return Ok(None);
+        };
+        let blob = self
+            .repo
+            .find_blob(entry.id)
+            .with_context(|| format!("Failed to read staged blob for {}", path.display()))?;
+        Ok(Some(blob.content().to_vec()))
+    }
+
+    /// Size in bytes of a file's staged (index) blob, or `None` if it isn't in the index
+    pub fn staged_size(&self, path: &std::path::Path) -> Result<Option<u64>> {
+        let index = self.repo.index().context("Failed to read the index")?;
+        let Some(entry) = index.get_path(path, 0) else {
+            return Ok(None);
+        };
+        // The index entry's own file_size is truncated to 32 bits, so ask the object database
+        let (size, _) = self
+            .repo
+            .odb()
+            .and_then(|odb| odb.read_header(entry.id))
+            .with_context(|| format!("Failed to read staged blob for {}", path.display()))?;
+        Ok(Some(size as u64))
+    }
+
+    /// Staged files that also have unstaged changes in the worktree
+    pub fn partially_staged_files(&self) -> Result<Vec<PathBuf>> {
+        let mut options = git2::StatusOptions::new();
+        options.include_untracked(false).exclude_submodules(true);
+        let statuses = self
+            .repo
+            .statuses(Some(&mut options))
+            .context("Failed to read repository status")?;
+
+        let staged = git2::Status::INDEX_NEW
+            | git2::Status::INDEX_MODIFIED
+            | git2::Status::INDEX_RENAMED
+            | git2::Status::INDEX_TYPECHANGE;
+        let unstaged =
+            git2::Status::WT_MODIFIED | git2::Status::WT_DELETED | git2::Status::WT_TYPECHANGE;
+
+        Ok(statuses
+            .iter()
+            .filter(|entry| {
+                entry.status().intersects(staged) && entry.status().intersects(unstaged)
+            })
+            .filter_map(|entry| entry.path().map(PathBuf::from))
+            .collect())
+    }
+
+    /// Write the files of the index (the staged snapshot) into `dir`, leaving the worktree alone
+    pub fn checkout_index_to(&self, dir: &std::path::Path) -> Result<()> {
+        let mut index = self.repo.index().context("Failed to read the index")?;
+        let mut checkout = git2::build::CheckoutBuilder::new();
+        checkout
+            .target_dir(dir)
+            .force()
+            .recreate_missing(true)
+            .update_index(false);
+        self.repo
+            .checkout_index(Some(&mut index), Some(&mut checkout))
+            .with_context(|| format!("Failed to check out the index to {}", dir.display()))
+    }
+
     /// Get the current branch name
     pub fn current_branch(&self) -> Result<Option<String>> {
         let head = match self.repo.head() {
@@ -85,6 +232,36 @@ impl GitRepository {
         }
     }
 
+    /// Get the full SHA of the commit HEAD points to, if any
+    pub fn head_sha(&self) -> Option<String> {
+        let head = self.repo.head().ok()?;
+        head.target().map(|oid| oid.to_string())
+    }
+
+    /// Commits on `branch` (or HEAD) that aren't on `upstream`, oldest first: the
+    /// commits a rebase onto `upstream` rewrites
+    pub fn commits_between(&self, upstream: &str, branch: Option<&str>) -> Result<Vec<String>> {
+        let resolve = |rev: &str| -> Result<git2::Oid> {
+            let commit = self
+                .repo
+                .revparse_single(rev)
+                .and_then(|object| object.peel_to_commit())
+                .with_context(|| format!("Failed to resolve '{}' to a commit", rev))?;
+            Ok(commit.id())
+        };
+
+        let mut walk = self.repo.revwalk().context("Failed to walk commits")?;
+        walk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)?;
+        walk.push(resolve(branch.unwrap_or("HEAD"))?)?;
+        walk.hide(resolve(upstream)?)?;
+        walk.map(|oid| Ok(oid?.to_string())).collect()
+    }
+
+    /// The `core.commentChar` setting, if configured
+    pub fn comment_char(&self) -> Option<String> {
+        self.repo.config().ok()?.get_string("core.commentChar").ok()
+    }
+
     /// Check if we're in a detached HEAD state
     pub fn is_detached(&self) -> bool {
         self.repo.head_detached().unwrap_or(false)
@@ -154,4 +331,140 @@ mod tests {
         let files = repo.staged_files().unwrap();
         assert!(files.is_empty());
     }
+
+    #[test]
+    fn test_staged_entries_status() {
+        let (temp_dir, repo) = init_test_repo();
+        let root = temp_dir.path();
+        let raw = Repository::open(root).unwrap();
+
+        // Commit an initial file
+        std::fs::write(root.join("existing.rs"), "fn main() {}\n").unwrap();
+        let mut index = raw.index().unwrap();
+        index.add_path(std::path::Path::new("existing.rs")).unwrap();
+        index.write().unwrap();
+        let tree = raw.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        raw.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+            .unwrap();
+
+        // Stage a modification and a new file
+        std::fs::write(root.join("existing.rs"), "fn main() { println!(); }\n").unwrap();
+        std::fs::write(root.join("new.rs"), "pub fn added() {}\n").unwrap();
+        index.add_path(std::path::Path::new("existing.rs")).unwrap();
+        index.add_path(std::path::Path::new("new.rs")).unwrap();
+        index.write().unwrap();
+
+        let mut entries = repo.staged_entries().unwrap();
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        assert_eq!(
+            entries,
+            vec![
+                StagedFile {
+                    path: PathBuf::from("existing.rs"),
+                    status: FileStatus::Modified,
+                },
+                StagedFile {
+                    path: PathBuf::from("new.rs"),
+                    status: FileStatus::Added,
+                },
+            ]
+        );
+    }
+
+    /// Commit an empty tree on HEAD with the given message
+    fn commit_empty(raw: &Repository, message: &str) -> git2::Oid {
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        let tree_id = raw.treebuilder(None).unwrap().write().unwrap();
+        let tree = raw.find_tree(tree_id).unwrap();
+        let parent = raw.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        raw.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_commits_between_lists_rebased_commits_oldest_first() {
+        let (temp_dir, repo) = init_test_repo();
+        let raw = Repository::open(temp_dir.path()).unwrap();
+        let base = commit_empty(&raw, "base");
+        raw.branch("upstream", &raw.find_commit(base).unwrap(), false)
+            .unwrap();
+        let first = commit_empty(&raw, "first");
+        let second = commit_empty(&raw, "second");
+
+        assert_eq!(
+            repo.commits_between("upstream", None).unwrap(),
+            vec![first.to_string(), second.to_string()]
+        );
+        assert_eq!(
+            repo.commits_between("upstream", Some(&first.to_string()))
+                .unwrap(),
+            vec![first.to_string()]
+        );
+        assert!(repo.commits_between("no-such-branch", None).is_err());
+    }
+
+    #[test]
+    fn test_staged_files_honor_index_file() {
+        let (temp_dir, _) = init_test_repo();
+        let root = temp_dir.path();
+        let raw = Repository::open(root).unwrap();
+
+        // The crafted index only has a.rs staged; the default one has both files
+        std::fs::write(root.join("a.rs"), "fn a() {}\n").unwrap();
+        std::fs::write(root.join("b.rs"), "fn b() {}\n").unwrap();
+        let mut index = raw.index().unwrap();
+        index.add_path(std::path::Path::new("a.rs")).unwrap();
+        index.write().unwrap();
+        let index_file = root.join("crafted-index");
+        std::fs::copy(root.join(".git/index"), &index_file).unwrap();
+        index.add_path(std::path::Path::new("b.rs")).unwrap();
+        index.write().unwrap();
+
+        let repo = GitRepository::with_index_file(Repository::open(root).unwrap(), None).unwrap();
+        let mut files = repo.staged_files().unwrap();
+        files.sort();
+        assert_eq!(files, vec![PathBuf::from("a.rs"), PathBuf::from("b.rs")]);
+
+        let repo = GitRepository::with_index_file(
+            Repository::open(root).unwrap(),
+            Some(index_file.into_os_string()),
+        )
+        .unwrap();
+        assert_eq!(repo.staged_files().unwrap(), vec![PathBuf::from("a.rs")]);
+    }
+
+    #[test]
+    fn test_staged_entries_skip_submodule_gitlinks() {
+        let (temp_dir, repo) = init_test_repo();
+        let root = temp_dir.path();
+        let raw = Repository::open(root).unwrap();
+
+        // Stage a regular file and a gitlink as `git submodule add` would
+        std::fs::write(root.join("lib.rs"), "pub fn f() {}\n").unwrap();
+        let mut index = raw.index().unwrap();
+        index.add_path(std::path::Path::new("lib.rs")).unwrap();
+        let time = git2::IndexTime::new(0, 0);
+        index
+            .add(&git2::IndexEntry {
+                ctime: time,
+                mtime: time,
+                dev: 0,
+                ino: 0,
+                mode: 0o160000,
+                uid: 0,
+                gid: 0,
+                file_size: 0,
+                id: git2::Oid::from_str("0123456789abcdef0123456789abcdef01234567").unwrap(),
+                flags: 0,
+                flags_extended: 0,
+                path: b"vendor/dep".to_vec(),
+            })
+            .unwrap();
+        index.write().unwrap();
+
+        let files = repo.staged_files().unwrap();
+        assert_eq!(files, vec![PathBuf::from("lib.rs")]);
+    }
 }
@@ -2,6 +2,7 @@
 
 use super::{GitRepository, HookTemplate};
 use crate::config::HookType;
+use crate::utils::write_atomic;
 use anyhow::{Context, Result};
 use std::fs;
 use std::path::Path;
@@ -21,15 +22,39 @@ impl HookInstaller {
         Ok(Self { repo })
     }
 
+    /// Create a HookInstaller for an already opened repository
+    #[allow(dead_code)]
+    pub fn from_repo(repo: GitRepository) -> Self {
+        Self { repo }
+    }
+
     /// Install a specific hook
     pub fn install_hook(&self, hook_type: HookType) -> Result<()> {
+        self.write_hook(hook_type, &HookTemplate::generate(hook_type))
+    }
+
+    /// Install `hook_type` so that it runs the configured hook `hook_name`
+    pub fn install_hook_as(&self, hook_type: HookType, hook_name: &str) -> Result<()> {
+        self.write_hook(hook_type, &HookTemplate::generate_for(hook_type, hook_name))
+    }
+
+    /// Install the post-checkout hook that keeps installed hooks in sync after checkouts,
+    /// running the configured hook `run_hook` first if there is one
+    pub fn install_auto_install(&self, run_hook: Option<&str>) -> Result<()> {
+        self.write_hook(
+            HookType::PostCheckout,
+            &HookTemplate::generate_auto_install(run_hook),
+        )
+    }
+
+    /// Write a hook script, backing up any existing hook not installed by FastHooks
+    fn write_hook(&self, hook_type: HookType, hook_content: &str) -> Result<()> {
         let hooks_dir = self.repo.hooks_dir();
 
         // Ensure hooks directory exists
         fs::create_dir_all(&hooks_dir).context("Failed to create hooks directory")?;
 
         let hook_path = hooks_dir.join(hook_type.as_str());
-        let hook_content = HookTemplate::generate(hook_type);
 
         // Backup existing hook if it exists and isn't ours
         if hook_path.exists() {
@@ -41,8 +66,8 @@ impl HookInstaller {
             }
         }
 
-        // Write the hook
-        fs::write(&hook_path, hook_content)
+        // Write the hook atomically: it may be rewritten while running (auto-install)
+        write_atomic(&hook_path, hook_content)
             .with_context(|| format!("Failed to write {} hook", hook_type))?;
 
         // Make executable on Unix
@@ -92,6 +117,34 @@ impl HookInstaller {
         Ok(())
     }
 
+    /// List the hooks currently installed by FastHooks
+    pub fn installed_hooks(&self) -> Vec<HookType> {
+        let hooks_dir = self.repo.hooks_dir();
+        HookType::all()
+            .iter()
+            .copied()
+            .filter(|hook_type| {
+                fs::read_to_string(hooks_dir.join(hook_type.as_str()))
+                    .map(|content| content.contains("fasthooks"))
+                    .unwrap_or(false)
+            })
+            .collect()
+    }
+
+    /// Uninstall FastHooks hooks that are not in `keep`, returning the removed ones
+    pub fn remove_stale(&self, keep: &[HookType]) -> Result<Vec<HookType>> {
+        let stale: Vec<HookType> = self
+            .installed_hooks()
+            .into_iter()
+            .filter(|hook_type| !keep.contains(hook_type))
+            .collect();
+
+        for hook_type in &stale {
+            self.uninstall_hook(*hook_type)?;
+        }
+        Ok(stale)
+    }
+
     /// Uninstall all FastHooks hooks
     pub fn uninstall_all(&self) -> Result<()> {
         for hook_type in HookType::all() {
@@ -151,4 +204,64 @@ mod tests {
             std::panic::resume_unwind(e);
         }
     }
+
+    #[test]
+    fn test_auto_install_hook() {
+        let temp_dir = TempDir::new().unwrap();
+        Repository::init(temp_dir.path()).unwrap();
+        let installer = HookInstaller::from_repo(GitRepository::open(temp_dir.path()).unwrap());
+
+        installer.install_auto_install(None).unwrap();
+
+        let hook_path = temp_dir.path().join(".git/hooks/post-checkout");
+        let content = fs::read_to_string(&hook_path).unwrap();
+        assert!(content.contains("fasthooks install"));
+
+        // Our own hook can be replaced and removed like any other
+        installer.install_hook(HookType::PostCheckout).unwrap();
+        assert!(!temp_dir
+            .path()
+            .join(".git/hooks/post-checkout.backup")
+            .exists());
+        installer.uninstall_hook(HookType::PostCheckout).unwrap();
+        assert!(!hook_path.exists());
+    }
+
+    #[test]
+    fn test_remove_stale_hooks() {
+        let temp_dir = TempDir::new().unwrap();
+        Repository::init(temp_dir.path()).unwrap();
+        let installer = HookInstaller::from_repo(GitRepository::open(temp_dir.path()).unwrap());
+        let hooks_dir = temp_dir.path().join(".git/hooks");
+
+        installer.install_hook(HookType::PreCommit).unwrap();
+        installer.install_hook(HookType::PrePush).unwrap();
+        fs::write(hooks_dir.join("commit-msg"), "#!/bin/sh\necho custom\n").unwrap();
+
+        let removed = installer.remove_stale(&[HookType::PreCommit]).unwrap();
+
+        assert_eq!(removed, vec![HookType::PrePush]);
+        assert!(hooks_dir.join("pre-commit").exists());
+        assert!(!hooks_dir.join("pre-push").exists());
+        // Hooks not installed by FastHooks are left alone
+        assert!(hooks_dir.join("commit-msg").exists());
+    }
+
+    #[test]
+    fn test_remove_stale_restores_backup() {
+        let temp_dir = TempDir::new().unwrap();
+        Repository::init(temp_dir.path()).unwrap();
+        let installer = HookInstaller::from_repo(GitRepository::open(temp_dir.path()).unwrap());
+        let hook_path = temp_dir.path().join(".git/hooks/pre-push");
+
+        fs::create_dir_all(hook_path.parent().unwrap()).unwrap();
+        fs::write(&hook_path, "#!/bin/sh\necho original\n").unwrap();
+        installer.install_hook(HookType::PrePush).unwrap();
+
+        installer.remove_stale(&[]).unwrap();
+
+        let content = fs::read_to_string(&hook_path).unwrap();
+        assert!(content.contains("echo original"));
+        assert!(installer.installed_hooks().is_empty());
+    }
 }
@@ -6,7 +6,7 @@ mod git;
 mod installer;
 mod template;
 
-pub use git::GitRepository;
+pub use git::{FileStatus, GitRepository};
 pub use installer::HookInstaller;
 pub use template::HookTemplate;
 
@@ -1,6 +1,6 @@
 //! Git hook script templates
 
-use crate::config::HookType;
+use crate::config::{HookType, ALT_CONFIG_FILE_NAMES, CONFIG_FILE_NAME};
 
 /// Generates hook script content
 pub struct HookTemplate;
@@ -8,13 +8,22 @@ pub struct HookTemplate;
 impl HookTemplate {
     /// Generate a hook script for the given hook type
     pub fn generate(hook_type: HookType) -> String {
-        let hook_name = hook_type.as_str();
+        Self::generate_for(hook_type, hook_type.as_str())
+    }
+
+    /// Generate a hook script for `hook_type` that runs the configured hook `hook_name`
+    pub fn generate_for(hook_type: HookType, hook_name: &str) -> String {
+        let header = if hook_name == hook_type.as_str() {
+            hook_name.to_string()
+        } else {
+            format!("{} (runs '{}')", hook_type, hook_name)
+        };
 
         format!(
             r#"#!/bin/sh
 # FastHooks - https://github.com/alfredo-baratta/fasthooks
 # This hook was automatically generated. Do not edit.
-# Hook: {hook_name}
+# Hook: {header}
 
 # Exit on error
 set -e
@@ -33,10 +42,52 @@ exit_code=$?
 
 exit $exit_code
 "#,
+            header = header,
             hook_name = hook_name
         )
     }
 
+    /// Generate a post-checkout hook that re-installs hooks after each checkout,
+    /// optionally running the configured hook `run_hook` first
+    pub fn generate_auto_install(run_hook: Option<&str>) -> String {
+        let run_tasks = match run_hook {
+            Some(hook_name) => format!(
+                "\n# Run the configured post-checkout tasks\nfasthooks run {} \"$@\"\nstatus=$?\n",
+                hook_name
+            ),
+            None => String::new(),
+        };
+        let has_config = std::iter::once(CONFIG_FILE_NAME)
+            .chain(ALT_CONFIG_FILE_NAMES.iter().copied())
+            .map(|name| format!("[ -f {} ]", name))
+            .collect::<Vec<_>>()
+            .join(" || ");
+
+        format!(
+            r#"#!/bin/sh
+# FastHooks - https://github.com/alfredo-baratta/fasthooks
+# This hook was automatically generated. Do not edit.
+# Hook: post-checkout (auto-install)
+
+# Never block a checkout because fasthooks is missing
+if ! command -v fasthooks >/dev/null 2>&1; then
+    exit 0
+fi
+
+status=0
+{run_tasks}
+# Keep installed hooks in sync with the checked-out configuration
+if {has_config}; then
+    fasthooks install >/dev/null 2>&1 || true
+fi
+
+exit $status
+"#,
+            run_tasks = run_tasks,
+            has_config = has_config
+        )
+    }
+
     /// Generate a Windows batch file hook
     #[allow(dead_code)]
     pub fn generate_windows(hook_type: HookType) -> String {
@@ -76,6 +127,13 @@ mod tests {
         assert!(script.contains("#!/bin/sh"));
     }
 
+    #[test]
+    fn test_generate_custom_named_hook() {
+        let script = HookTemplate::generate_for(HookType::PrePush, "ci");
+        assert!(script.contains("# Hook: pre-push (runs 'ci')"));
+        assert!(script.contains("fasthooks run ci \"$@\""));
+    }
+
     #[test]
     fn test_generate_windows_hook() {
         let script = HookTemplate::generate_windows(HookType::PreCommit);
@@ -83,4 +141,18 @@ mod tests {
         assert!(script.contains("pre-commit"));
         assert!(script.contains("@echo off"));
     }
+
+    #[test]
+    fn test_generate_auto_install_hook() {
+        let script = HookTemplate::generate_auto_install(None);
+        assert!(script.contains("#!/bin/sh"));
+        assert!(script.contains("fasthooks install"));
+        assert!(script.contains("[ -f fasthooks.toml ]"));
+        assert!(script.contains("[ -f .fasthooks.yml ]"));
+        assert!(!script.contains("fasthooks run"));
+
+        let script = HookTemplate::generate_auto_install(Some("post-checkout"));
+        assert!(script.contains("fasthooks run post-checkout \"$@\""));
+        assert!(script.find("fasthooks run").unwrap() < script.find("fasthooks install").unwrap());
+    }
 }
@@ -23,16 +23,26 @@ fn main() -> Result<()> {
         .init();
 
     let cli = Cli::parse();
+    cli.color.apply();
 
-    match cli.command {
+    let Some(command) = cli.command else {
+        return commands::status::run();
+    };
+
+    match command {
         Commands::Init { force } => commands::init::run(force),
-        Commands::Install { hook } => commands::install::run(hook),
-        Commands::Uninstall => commands::uninstall::run(),
-        Commands::Run { hook, files, args } => commands::run::run(hook, files, args),
+        Commands::Install { hook, sync } => commands::install::run(hook, sync),
+        Commands::Uninstall { purge, yes } => commands::uninstall::run(purge, yes),
+        Commands::Run(args) => commands::run::run(*args, cli.verbose),
         Commands::Add { hook, command } => commands::add::run(hook, command),
-        Commands::List => commands::list::run(),
-        Commands::Validate => commands::validate::run(),
+        Commands::List { tree, timings } => commands::list::run(tree, timings),
+        Commands::Validate {
+            explain,
+            check_scripts,
+        } => commands::validate::run(explain, check_scripts),
+        Commands::Config { action } => commands::config::run(action),
         Commands::Migrate => commands::migrate::run(),
-        Commands::Benchmark => commands::benchmark::run(),
+        Commands::Benchmark { iterations } => commands::benchmark::run(iterations),
+        Commands::Status => commands::status::run(),
     }
 }
@@ -1,137 +1,586 @@
 //! Task execution engine with parallel support, conditions, dependencies, and glob patterns
 
-use super::{HookResult, TaskResult};
-use crate::config::{Hook, Settings, Task};
-use crate::hooks::GitRepository;
+use super::{
+    preflight, shuffle, ExecutionStats, HookResult, LastRun, SkippedTask, TaskExplanation,
+    TaskResult,
+};
+use crate::config::{ConcurrencyStrategy, Hook, OutputMode, Settings, Task, TaskCommand};
+use crate::hooks::{FileStatus, GitRepository};
+use crate::utils::expand_vars;
 use anyhow::{Context, Result};
-use glob::Pattern;
-use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use colored::Colorize;
+use glob::{MatchOptions, Pattern};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::future::Future;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::io::AsyncWriteExt;
 use tokio::process::Command;
 use tokio::sync::{Mutex, Semaphore};
 
+/// How the tasks of a hook are scheduled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExecutionMode {
+    /// Independent tasks run concurrently
+    Parallel,
+    /// Tasks run concurrently as soon as their dependencies have finished
+    Dependencies,
+    /// Tasks run one at a time in dependency order
+    Sequential,
+}
+
+impl ExecutionMode {
+    /// Pick the scheduling mode; `parallel = false` always runs sequentially
+    fn select(parallel: bool, has_dependencies: bool) -> Self {
+        match (parallel, has_dependencies) {
+            (true, false) => Self::Parallel,
+            (true, true) => Self::Dependencies,
+            (false, _) => Self::Sequential,
+        }
+    }
+}
+
 /// Executes hook tasks with parallel support
 pub struct TaskExecutor {
     settings: Settings,
     staged_files: Vec<PathBuf>,
+    file_statuses: HashMap<PathBuf, FileStatus>,
     current_branch: Option<String>,
+    current_sha: Option<String>,
     hook_args: Vec<String>,
+    passthrough_args: Vec<String>,
+    shuffle_seed: Option<u64>,
+    work_dir: Option<PathBuf>,
+    dump_env_dir: Option<PathBuf>,
+    commits: Option<Vec<String>>,
+    max_failures: Option<usize>,
 }
 
 impl TaskExecutor {
     /// Create a new TaskExecutor
     pub fn new(settings: Settings) -> Result<Self> {
         let repo = GitRepository::discover()?;
-        let staged_files = repo.staged_files().unwrap_or_default();
+        Ok(Self::from_repo(settings, &repo))
+    }
+
+    /// Create a TaskExecutor from an already opened repository.
+    ///
+    /// If the index can't be read (corrupt index, unusual repository state) the
+    /// staged set is left empty rather than failing: globbed tasks then skip and
+    /// the others still run, so a broken index doesn't block every commit.
+    pub fn from_repo(settings: Settings, repo: &GitRepository) -> Self {
+        let entries = repo.staged_entries().unwrap_or_else(|e| {
+            tracing::warn!("Proceeding without staged files: {:#}", e);
+            Vec::new()
+        });
         let current_branch = repo.current_branch().unwrap_or(None);
 
-        Ok(Self {
+        Self {
             settings,
-            staged_files,
+            staged_files: Self::dedupe_paths(entries.iter().map(|e| e.path.clone()).collect()),
+            file_statuses: entries.into_iter().map(|e| (e.path, e.status)).collect(),
             current_branch,
+            current_sha: repo.head_sha(),
             hook_args: Vec::new(),
-        })
+            passthrough_args: Vec::new(),
+            shuffle_seed: None,
+            work_dir: None,
+            dump_env_dir: None,
+            commits: None,
+            max_failures: None,
+        }
     }
 
     /// Create a TaskExecutor with specific files (for manual runs)
     pub fn with_files(settings: Settings, files: Vec<PathBuf>) -> Result<Self> {
         let repo = GitRepository::discover().ok();
-        let current_branch = repo.and_then(|r| r.current_branch().ok()).flatten();
+        let current_branch = repo
+            .as_ref()
+            .and_then(|r| r.current_branch().ok())
+            .flatten();
+        let current_sha = repo.as_ref().and_then(|r| r.head_sha());
 
         Ok(Self {
             settings,
-            staged_files: files,
+            staged_files: Self::dedupe_paths(files),
+            file_statuses: HashMap::new(),
             current_branch,
+            current_sha,
             hook_args: Vec::new(),
+            passthrough_args: Vec::new(),
+            shuffle_seed: None,
+            work_dir: None,
+            dump_env_dir: None,
+            commits: None,
+            max_failures: None,
         })
     }
 
+    /// Create a TaskExecutor that doesn't touch git (no branch, SHA or file statuses)
+    pub fn without_git(settings: Settings, files: Vec<PathBuf>) -> Self {
+        Self {
+            settings,
+            staged_files: Self::dedupe_paths(files),
+            file_statuses: HashMap::new(),
+            current_branch: None,
+            current_sha: None,
+            hook_args: Vec::new(),
+            passthrough_args: Vec::new(),
+            shuffle_seed: None,
+            work_dir: None,
+            dump_env_dir: None,
+            commits: None,
+            max_failures: None,
+        }
+    }
+
+    /// Normalize separators and `./` segments, then drop repeated paths, keeping the first
+    fn dedupe_paths(files: Vec<PathBuf>) -> Vec<PathBuf> {
+        let mut seen = HashSet::new();
+        files
+            .into_iter()
+            .map(|f| {
+                let normalized = f.to_string_lossy().replace('\\', "/");
+                Path::new(&normalized)
+                    .components()
+                    .filter(|c| !matches!(c, std::path::Component::CurDir))
+                    .collect::<PathBuf>()
+            })
+            .filter(|f| seen.insert(f.clone()))
+            .collect()
+    }
+
+    /// Owned copy of the execution context for running one task on its own tokio task.
+    /// Keeps every staged file so `{dirs}` in unfiltered tasks still sees them.
+    fn for_task(&self) -> TaskExecutor {
+        TaskExecutor {
+            settings: self.settings.clone(),
+            staged_files: self.staged_files.clone(),
+            file_statuses: self.file_statuses.clone(),
+            current_branch: self.current_branch.clone(),
+            current_sha: self.current_sha.clone(),
+            hook_args: self.hook_args.clone(),
+            passthrough_args: self.passthrough_args.clone(),
+            shuffle_seed: None,
+            work_dir: self.work_dir.clone(),
+            dump_env_dir: self.dump_env_dir.clone(),
+            commits: self.commits.clone(),
+            max_failures: self.max_failures,
+        }
+    }
+
+    /// Write each task's full environment to `<dir>/<task>.env` before running it
+    pub fn dump_env_to(mut self, dir: &Path) -> Self {
+        self.dump_env_dir = Some(dir.to_path_buf());
+        self
+    }
+
+    /// Stop scheduling new tasks after `max_failures` failures (`--max-failures`),
+    /// overriding both the hook's `fail_fast` and the settings
+    pub fn with_max_failures(mut self, max_failures: usize) -> Self {
+        self.max_failures = Some(max_failures);
+        self
+    }
+
+    /// Run `per_commit` tasks once for each of these commits (oldest first)
+    pub fn with_commits(mut self, commits: Vec<String>) -> Self {
+        self.commits = Some(commits);
+        self
+    }
+
     /// Set hook arguments (passed from git)
     pub fn with_hook_args(mut self, args: Vec<String>) -> Self {
         self.hook_args = args;
         self
     }
 
+    /// Set extra arguments appended to every task's command (`fasthooks run <hook> -- <args>`)
+    pub fn with_passthrough_args(mut self, args: Vec<String>) -> Self {
+        self.passthrough_args = args;
+        self
+    }
+
+    /// Randomize the order of independent tasks with a reproducible seed
+    pub fn with_shuffle(mut self, seed: u64) -> Self {
+        self.shuffle_seed = Some(seed);
+        self
+    }
+
+    /// Run tasks in `dir` instead of the current directory (task `cwd` is relative to it)
+    pub fn in_dir(mut self, dir: &Path) -> Self {
+        self.work_dir = Some(dir.to_path_buf());
+        self
+    }
+
+    /// Directory a task runs in: its `cwd` (default `.`), relative to the work dir if any
+    pub fn task_cwd(&self, task: &Task) -> PathBuf {
+        let cwd = task
+            .cwd
+            .as_deref()
+            .map(expand_vars)
+            .unwrap_or_else(|| ".".to_string());
+        match &self.work_dir {
+            Some(dir) => dir.join(cwd),
+            None => PathBuf::from(cwd),
+        }
+    }
+
+    /// Only consider files (relative to `root`) modified after `since`
+    pub fn modified_since(mut self, root: &Path, since: SystemTime) -> Self {
+        self.staged_files = LastRun::modified_since(&self.staged_files, root, since);
+        self
+    }
+
     /// Execute all tasks in a hook
     pub async fn execute_hook(&self, hook: &Hook) -> Result<HookResult> {
         let start = Instant::now();
         let parallel = hook.parallel.unwrap_or(self.settings.parallel);
-        let fail_fast = hook.fail_fast.unwrap_or(self.settings.fail_fast);
+        let max_failures = self.failure_limit(hook);
+
+        // Insert the branch-derived prefix into the commit message before tasks see it
+        if let Some(pattern) = &hook.branch_prefix_pattern {
+            self.apply_branch_prefix(pattern)?;
+        }
 
         // Sort tasks by dependencies (topological sort)
-        let sorted_tasks = self.sort_tasks_by_dependencies(&hook.tasks)?;
+        let sorted_tasks = Self::sort_tasks_by_dependencies(&hook.tasks)?;
 
-        // Filter tasks by conditions
-        let executable_tasks: Vec<&Task> = sorted_tasks
+        // Filter tasks by conditions; protected branches run everything
+        let protected = self.on_protected_branch();
+        let (mut executable_tasks, unmet): (Vec<&Task>, Vec<&Task>) = sorted_tasks
             .into_iter()
-            .filter(|t| self.evaluate_condition(t))
-            .collect();
+            .partition(|t| protected || self.evaluate_condition(t));
+
+        if let Some(seed) = self.shuffle_seed {
+            shuffle::shuffle_independent(&mut executable_tasks, seed);
+        }
+
+        // Tasks whose program is missing and whose fallback is empty don't run
+        let (executable_tasks, unavailable): (Vec<&Task>, Vec<&Task>) = executable_tasks
+            .into_iter()
+            .partition(|t| self.effective_run(t).is_some());
+
+        // Per-commit tasks need a commit range to iterate
+        let has_commits = self.commits.as_ref().is_some_and(|c| !c.is_empty());
+        let (executable_tasks, no_commits): (Vec<&Task>, Vec<&Task>) = executable_tasks
+            .into_iter()
+            .partition(|t| !t.per_commit || has_commits);
+
+        if self.settings.preflight {
+            self.preflight_check(&executable_tasks)?;
+        }
 
-        let results = if parallel && !self.has_dependencies(&executable_tasks) {
-            self.execute_parallel(&executable_tasks, fail_fast).await?
+        let mut skipped: Vec<SkippedTask> = unmet
+            .iter()
+            .map(|task| SkippedTask {
+                name: task.name.clone(),
+                reason: format!(
+                    "condition '{}' is false",
+                    task.condition.as_deref().unwrap_or_default().trim()
+                ),
+            })
+            .collect();
+        skipped.extend(self.glob_skips(&executable_tasks));
+        skipped.extend(no_commits.iter().map(|task| SkippedTask {
+            name: task.name.clone(),
+            reason: "no commits to check (per_commit runs in pre-rebase)".to_string(),
+        }));
+        skipped.extend(unavailable.iter().map(|task| SkippedTask {
+            name: task.name.clone(),
+            reason: format!(
+                "{} is not installed and the fallback is empty",
+                task.platform_run()
+                    .commands()
+                    .first()
+                    .and_then(|command| preflight::program_names(command).first().copied())
+                    .unwrap_or("command")
+            ),
+        }));
+
+        // Interactive tasks own the terminal, so they run first, one at a time
+        let (prelude, executable_tasks) = Self::split_interactive(executable_tasks);
+        let mut results = self.execute_sequential(&prelude, max_failures).await?;
+        let prelude_failures = results
+            .iter()
+            .filter(|r| !r.success && prelude.iter().any(|t| t.name == r.name && !t.allow_failure))
+            .count();
+        if Self::limit_reached(prelude_failures, max_failures) {
+            return self.finish_hook(hook, results, skipped, start);
+        }
+        // Failures in the prelude count towards the limit of the remaining tasks
+        let max_failures = if max_failures == 0 {
+            0
         } else {
-            self.execute_with_dependencies(&executable_tasks, fail_fast, parallel)
-                .await?
+            max_failures - prelude_failures
+        };
+
+        // Tasks reading from the terminal can't share it with concurrent tasks
+        let parallel = parallel && !executable_tasks.iter().any(|t| t.inherit_stdin);
+
+        let mode = ExecutionMode::select(parallel, self.has_dependencies(&executable_tasks));
+        let batch = match mode {
+            ExecutionMode::Parallel => {
+                self.execute_parallel(&executable_tasks, max_failures)
+                    .await?
+            }
+            ExecutionMode::Dependencies => {
+                self.execute_with_dependencies(&executable_tasks, max_failures)
+                    .await?
+            }
+            // Tasks are already topologically sorted, so dependencies run first
+            ExecutionMode::Sequential => {
+                self.execute_sequential(&executable_tasks, max_failures)
+                    .await?
+            }
         };
+        results.extend(batch);
 
+        self.finish_hook(hook, results, skipped, start)
+    }
+
+    /// Build the hook result, in config order unless `output_mode` says otherwise,
+    /// appending the summary to the commit message if requested
+    fn finish_hook(
+        &self,
+        hook: &Hook,
+        mut results: Vec<TaskResult>,
+        skipped: Vec<SkippedTask>,
+        start: Instant,
+    ) -> Result<HookResult> {
         let total_duration = start.elapsed().as_millis() as u64;
-        Ok(HookResult::new(results, total_duration))
+        // Interactive tasks already wrote to the terminal, so they stay first
+        if self.settings.output_mode == OutputMode::BufferedOrdered {
+            let key = |name: &str| {
+                let position = hook.tasks.iter().position(|t| t.name == name);
+                let interactive = position.is_some_and(|i| hook.tasks[i].interactive);
+                (!interactive, position)
+            };
+            results.sort_by_key(|r| key(&r.name));
+        }
+        Ok(HookResult::new(results, total_duration).with_skipped(skipped))
     }
 
-    /// Check if any task has dependencies
-    fn has_dependencies(&self, tasks: &[&Task]) -> bool {
-        tasks.iter().any(|t| !t.depends_on.is_empty())
+    /// Split sorted tasks into the interactive prelude and the remaining batch,
+    /// keeping the dependency order within each
+    fn split_interactive(tasks: Vec<&Task>) -> (Vec<&Task>, Vec<&Task>) {
+        tasks.into_iter().partition(|t| t.interactive)
+    }
+
+    /// Collect tasks that will be skipped because their glob matches no files
+    fn glob_skips(&self, tasks: &[&Task]) -> Vec<SkippedTask> {
+        tasks
+            .iter()
+            .filter_map(|task| {
+                let filter = task.file_filter()?;
+                if !self.filter_files(task).is_empty() {
+                    return None;
+                }
+                let matched = self.matched_files(task).len();
+                let reason = if matched > 0 {
+                    format!("only deletions among {} files matching {}", matched, filter)
+                } else {
+                    format!(
+                        "no files matched {} among {} staged",
+                        filter,
+                        self.staged_files.len()
+                    )
+                };
+                Some(SkippedTask {
+                    name: task.name.clone(),
+                    reason,
+                })
+            })
+            .collect()
     }
 
-    /// Sort tasks by dependencies using topological sort
-    fn sort_tasks_by_dependencies<'a>(&self, tasks: &'a [Task]) -> Result<Vec<&'a Task>> {
-        let task_map: HashMap<&str, &Task> = tasks.iter().map(|t| (t.name.as_str(), t)).collect();
+    /// Command a task runs: its own, or its `fallback` when the first program of
+    /// its own command isn't installed. `None` means the fallback is empty (skip).
+    fn effective_run<'a>(&self, task: &'a Task) -> Option<&'a [String]> {
+        let run = task.platform_run().commands();
+        let Some(fallback) = task.fallback.as_ref() else {
+            return Some(run);
+        };
+
+        let cwd = self.task_cwd(task);
+        let missing = run
+            .first()
+            .and_then(|command| preflight::program_names(command).first().copied())
+            .is_some_and(|program| !preflight::is_resolvable(program, &cwd));
+        match (missing, fallback.trim().is_empty()) {
+            (false, _) => Some(run),
+            (true, false) => Some(std::slice::from_ref(fallback)),
+            (true, true) => None,
+        }
+    }
 
-        let mut in_degree: HashMap<&str, usize> = HashMap::new();
-        let mut graph: HashMap<&str, Vec<&str>> = HashMap::new();
+    /// Fail before running anything if a task's command can't be found
+    fn preflight_check(&self, tasks: &[&Task]) -> Result<()> {
+        let mut problems = Vec::new();
 
-        // Initialize
         for task in tasks {
-            in_degree.entry(task.name.as_str()).or_insert(0);
-            graph.entry(task.name.as_str()).or_default();
+            let cwd = self.task_cwd(task);
+
+            let commands = self.effective_run(task).unwrap_or_default();
+            let missing = commands
+                .iter()
+                .flat_map(|command| preflight::missing_programs(command, &cwd));
+            for program in missing {
+                let hint = if cwd.join("package.json").exists() {
+                    " — did you run npm install?"
+                } else {
+                    ""
+                };
+                problems.push(format!(
+                    "command not found: {} (task '{}'){}",
+                    program, task.name, hint
+                ));
+            }
+        }
+
+        if !problems.is_empty() {
+            anyhow::bail!("Pre-flight check failed:\n  {}", problems.join("\n  "));
+        }
+
+        Ok(())
+    }
+
+    /// Prepend the part of the branch name matched by `pattern` to the commit
+    /// message file (the first hook argument), unless it is already there
+    fn apply_branch_prefix(&self, pattern: &str) -> Result<()> {
+        let (Some(branch), Some(message_file)) =
+            (self.current_branch.as_deref(), self.hook_args.first())
+        else {
+            return Ok(());
+        };
+
+        let re = regex::Regex::new(pattern)
+            .with_context(|| format!("Invalid branch_prefix_pattern: {}", pattern))?;
+        let Some(captures) = re.captures(branch) else {
+            return Ok(());
+        };
+
+        let prefix = captures
+            .get(1)
+            .or_else(|| captures.get(0))
+            .map(|m| m.as_str())
+            .unwrap_or_default();
+        if prefix.is_empty() {
+            return Ok(());
+        }
+
+        let message = std::fs::read_to_string(message_file)
+            .with_context(|| format!("Failed to read commit message file: {}", message_file))?;
+        if message.starts_with(prefix) {
+            return Ok(());
+        }
+
+        std::fs::write(message_file, format!("{} {}", prefix, message))
+            .with_context(|| format!("Failed to write commit message file: {}", message_file))
+    }
+
+    /// Files selected by a task's glob, regex and status filters, without running it
+    pub fn task_files(&self, task: &Task) -> Vec<PathBuf> {
+        self.filter_files(task)
+    }
+
+    /// Explain whether a task would run against the current repository state
+    pub fn explain_task(&self, task: &Task) -> TaskExplanation {
+        let mut reasons = Vec::new();
+
+        if task.condition.is_some() && self.on_protected_branch() {
+            reasons.push("protected branch".to_string());
+        } else if let Some(condition) = &task.condition {
+            if !self.evaluate_condition(task) {
+                return TaskExplanation::skip(format!("condition '{}' is false", condition.trim()));
+            }
+            reasons.push("condition true".to_string());
+        }
+
+        if let Some(filter) = task.file_filter() {
+            let matched = self.filter_files(task).len();
+            if matched == 0 {
+                return TaskExplanation::skip(format!("no staged files match {}", filter));
+            }
+            reasons.push(if matched == 1 {
+                "1 file matches".to_string()
+            } else {
+                format!("{} files match", matched)
+            });
+        }
+
+        if reasons.is_empty() {
+            reasons.push("no condition or glob".to_string());
+        }
+
+        TaskExplanation::run(reasons.join(", "))
+    }
+
+    /// Tasks per CPU core under the `io` concurrency strategy
+    const IO_TASKS_PER_CPU: usize = 4;
+
+    /// Resolve the maximum number of concurrent tasks (0 = auto-detect)
+    fn max_parallel(&self) -> usize {
+        Self::worker_count(
+            self.settings.concurrency_strategy,
+            self.settings.max_parallel,
+            num_cpus::get(),
+        )
+    }
+
+    /// Concurrent tasks for a strategy; a non-zero `max_parallel` always wins
+    fn worker_count(strategy: ConcurrencyStrategy, max_parallel: usize, cpus: usize) -> usize {
+        match (strategy, max_parallel) {
+            (_, n) if n > 0 => n,
+            (ConcurrencyStrategy::Cpu, _) => cpus,
+            (ConcurrencyStrategy::Io, _) => cpus * Self::IO_TASKS_PER_CPU,
+            // `fixed` without a limit has nothing to go on, so run one at a time
+            (ConcurrencyStrategy::Fixed, _) => 1,
         }
+    }
+
+    /// Check if any task has dependencies
+    fn has_dependencies(&self, tasks: &[&Task]) -> bool {
+        tasks.iter().any(|t| !t.depends_on.is_empty())
+    }
+
+    /// Sort tasks by dependencies using topological sort.
+    ///
+    /// Ties are broken by config order, so the result is deterministic.
+    pub fn sort_tasks_by_dependencies(tasks: &[Task]) -> Result<Vec<&Task>> {
+        let index: HashMap<&str, usize> = tasks
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (t.name.as_str(), i))
+            .collect();
+
+        let mut in_degree: Vec<usize> = vec![0; tasks.len()];
+        let mut graph: Vec<Vec<usize>> = vec![Vec::new(); tasks.len()];
 
         // Build graph
-        for task in tasks {
+        for (i, task) in tasks.iter().enumerate() {
             for dep in &task.depends_on {
-                if task_map.contains_key(dep.as_str()) {
-                    graph.entry(dep.as_str()).or_default().push(&task.name);
-                    *in_degree.entry(task.name.as_str()).or_insert(0) += 1;
+                if let Some(&dep_index) = index.get(dep.as_str()) {
+                    graph[dep_index].push(i);
+                    in_degree[i] += 1;
                 }
             }
         }
 
-        // Kahn's algorithm
-        let mut queue: Vec<&str> = in_degree
-            .iter()
-            .filter(|(_, &degree)| degree == 0)
-            .map(|(&name, _)| name)
-            .collect();
+        // Kahn's algorithm, always taking the earliest ready task
+        let mut ready: BTreeSet<usize> = (0..tasks.len()).filter(|&i| in_degree[i] == 0).collect();
 
         let mut sorted = Vec::new();
 
-        while let Some(node) = queue.pop() {
-            if let Some(task) = task_map.get(node) {
-                sorted.push(*task);
-            }
+        while let Some(node) = ready.pop_first() {
+            sorted.push(&tasks[node]);
 
-            if let Some(neighbors) = graph.get(node) {
-                for &neighbor in neighbors {
-                    if let Some(degree) = in_degree.get_mut(neighbor) {
-                        *degree -= 1;
-                        if *degree == 0 {
-                            queue.push(neighbor);
-                        }
-                    }
+            for &neighbor in &graph[node] {
+                in_degree[neighbor] -= 1;
+                if in_degree[neighbor] == 0 {
+                    ready.insert(neighbor);
                 }
             }
         }
@@ -146,10 +595,33 @@ impl TaskExecutor {
 
     /// Evaluate task condition
     fn evaluate_condition(&self, task: &Task) -> bool {
-        let Some(condition) = &task.condition else {
-            return true;
+        task.condition.as_deref().map_or(true, |condition| {
+            Self::condition_holds(condition, self.current_branch.as_deref())
+        })
+    }
+
+    /// Whether the current branch matches one of `settings.protected_branches`
+    fn on_protected_branch(&self) -> bool {
+        let Some(branch) = self.current_branch.as_deref() else {
+            return false;
         };
+        self.settings
+            .protected_branches
+            .iter()
+            .filter_map(|pattern| Pattern::new(pattern).ok())
+            .any(|pattern| pattern.matches(branch))
+    }
 
+    /// Whether a hook's `if` condition holds on `branch` (hooks without one are always
+    /// enabled). Needs no executor, so a disabled hook can be skipped before any setup.
+    pub fn hook_enabled(hook: &Hook, branch: Option<&str>) -> bool {
+        hook.condition
+            .as_deref()
+            .map_or(true, |condition| Self::condition_holds(condition, branch))
+    }
+
+    /// Evaluate a task or hook condition such as `branch == main` or `env:CI`
+    fn condition_holds(condition: &str, branch: Option<&str>) -> bool {
         let condition = condition.trim();
 
         // Handle environment variable checks: "env:VAR_NAME"
@@ -164,17 +636,17 @@ impl TaskExecutor {
 
         // Handle branch conditions
         if condition.contains("branch") {
-            return self.evaluate_branch_condition(condition);
+            return Self::evaluate_branch_condition(condition, branch);
         }
 
-        // Handle file existence: "exists:path/to/file"
+        // Handle file existence: "exists:path/to/file" or "exists:src/**/*.rs"
         if let Some(path) = condition.strip_prefix("exists:") {
-            return std::path::Path::new(path.trim()).exists();
+            return Self::path_exists(path.trim());
         }
 
         // Handle negated file existence: "!exists:path/to/file"
         if let Some(path) = condition.strip_prefix("!exists:") {
-            return !std::path::Path::new(path.trim()).exists();
+            return !Self::path_exists(path.trim());
         }
 
         // Unknown condition format - default to true
@@ -182,9 +654,21 @@ impl TaskExecutor {
         true
     }
 
+    /// Check if a path exists, treating it as a glob when it contains wildcards
+    fn path_exists(path: &str) -> bool {
+        if !path.contains(['*', '?', '[']) {
+            return Path::new(path).exists();
+        }
+
+        match glob::glob(path) {
+            Ok(mut entries) => entries.any(|entry| entry.is_ok()),
+            Err(_) => false,
+        }
+    }
+
     /// Evaluate branch-based conditions
-    fn evaluate_branch_condition(&self, condition: &str) -> bool {
-        let branch = self.current_branch.as_deref().unwrap_or("");
+    fn evaluate_branch_condition(condition: &str, branch: Option<&str>) -> bool {
+        let branch = branch.unwrap_or("");
 
         // Parse condition: "branch == main" or "branch != main"
         if let Some(rest) = condition.strip_prefix("branch") {
@@ -209,19 +693,38 @@ impl TaskExecutor {
         true
     }
 
+    /// Number of failures that stop the hook (0 = never): `--max-failures` wins, then the
+    /// hook's own `fail_fast`, then `max_failures`, then the global `fail_fast`
+    fn failure_limit(&self, hook: &Hook) -> usize {
+        if let Some(max_failures) = self.max_failures {
+            return max_failures;
+        }
+        match hook.fail_fast {
+            Some(fail_fast) => usize::from(fail_fast),
+            None if self.settings.max_failures > 0 => self.settings.max_failures,
+            None => usize::from(self.settings.fail_fast),
+        }
+    }
+
+    /// Whether `failures` reaches the limit (0 = no limit)
+    fn limit_reached(failures: usize, max_failures: usize) -> bool {
+        max_failures > 0 && failures >= max_failures
+    }
+
     /// Execute tasks sequentially
     async fn execute_sequential(
         &self,
         tasks: &[&Task],
-        fail_fast: bool,
+        max_failures: usize,
     ) -> Result<Vec<TaskResult>> {
         let mut results = Vec::with_capacity(tasks.len());
+        let mut failures = 0;
 
         for task in tasks {
             let files = self.filter_files(task);
 
             // Skip if no matching files and glob is specified
-            if task.glob.is_some() && files.is_empty() {
+            if task.filters_files() && files.is_empty() {
                 continue;
             }
 
@@ -229,8 +732,11 @@ impl TaskExecutor {
             let failed = !result.success;
             results.push(result);
 
-            if failed && fail_fast && !task.allow_failure {
-                break;
+            if failed && !task.allow_failure {
+                failures += 1;
+                if Self::limit_reached(failures, max_failures) {
+                    break;
+                }
             }
         }
 
@@ -241,31 +747,22 @@ impl TaskExecutor {
     async fn execute_with_dependencies(
         &self,
         tasks: &[&Task],
-        fail_fast: bool,
-        parallel: bool,
+        max_failures: usize,
     ) -> Result<Vec<TaskResult>> {
-        if !parallel {
-            return self.execute_sequential(tasks, fail_fast).await;
-        }
-
-        let max_parallel = if self.settings.max_parallel == 0 {
-            num_cpus::get()
-        } else {
-            self.settings.max_parallel
-        };
-
+        let max_parallel = self.max_parallel();
         let semaphore = Arc::new(Semaphore::new(max_parallel));
         let completed: Arc<Mutex<HashSet<String>>> = Arc::new(Mutex::new(HashSet::new()));
         let results: Arc<Mutex<Vec<TaskResult>>> = Arc::new(Mutex::new(Vec::new()));
-        let failed = Arc::new(AtomicBool::new(false));
+        let failures = Arc::new(AtomicUsize::new(0));
+        let stopped = || Self::limit_reached(failures.load(Ordering::SeqCst), max_failures);
 
         // Create a map for quick task lookup
         let task_map: HashMap<&str, &Task> = tasks.iter().map(|t| (t.name.as_str(), *t)).collect();
 
         // Process tasks
         for task in tasks {
-            // Check fail_fast
-            if fail_fast && failed.load(Ordering::SeqCst) {
+            // Stop scheduling once enough tasks failed
+            if stopped() {
                 break;
             }
 
@@ -283,48 +780,48 @@ impl TaskExecutor {
 
                 tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
 
-                // Check if we should abort due to fail_fast
-                if fail_fast && failed.load(Ordering::SeqCst) {
+                // Check if we should abort because enough tasks failed
+                if stopped() {
                     break;
                 }
             }
 
-            if fail_fast && failed.load(Ordering::SeqCst) {
+            if stopped() {
                 break;
             }
 
             let files = self.filter_files(task);
 
             // Skip if no matching files and glob is specified
-            if task.glob.is_some() && files.is_empty() {
+            if task.filters_files() && files.is_empty() {
                 completed.lock().await.insert(task.name.clone());
                 continue;
             }
 
             let permit = semaphore.clone().acquire_owned().await?;
+            // A task that just released its permit may have hit the limit
+            if stopped() {
+                break;
+            }
             let task_clone = (*task).clone();
             let files_clone = files.clone();
             let completed_clone = completed.clone();
             let results_clone = results.clone();
-            let failed_clone = failed.clone();
-            let settings_clone = self.settings.clone();
-            let hook_args_clone = self.hook_args.clone();
+            let failures_clone = failures.clone();
+            let executor = self.for_task();
 
             tokio::spawn(async move {
-                let executor = TaskExecutor {
-                    settings: settings_clone,
-                    staged_files: files_clone.clone(),
-                    current_branch: None,
-                    hook_args: hook_args_clone,
-                };
-
                 let result = executor.execute_task(&task_clone, &files_clone).await;
-                drop(permit);
 
-                if let Ok(res) = result {
+                // Count the failure before another task can take the permit
+                if let Ok(ref res) = result {
                     if !res.success && !task_clone.allow_failure {
-                        failed_clone.store(true, Ordering::SeqCst);
+                        failures_clone.fetch_add(1, Ordering::SeqCst);
                     }
+                }
+                drop(permit);
+
+                if let Ok(res) = result {
                     results_clone.lock().await.push(res);
                 }
 
@@ -338,7 +835,7 @@ impl TaskExecutor {
             let expected = tasks
                 .iter()
                 .filter(|t| {
-                    if t.glob.is_some() {
+                    if t.filters_files() {
                         !self.filter_files(t).is_empty()
                     } else {
                         true
@@ -346,7 +843,7 @@ impl TaskExecutor {
                 })
                 .count();
 
-            if completed_count >= expected || (fail_fast && failed.load(Ordering::SeqCst)) {
+            if completed_count >= expected || stopped() {
                 break;
             }
 
@@ -361,53 +858,46 @@ impl TaskExecutor {
     }
 
     /// Execute tasks in parallel (no dependencies)
-    async fn execute_parallel(&self, tasks: &[&Task], fail_fast: bool) -> Result<Vec<TaskResult>> {
-        let max_parallel = if self.settings.max_parallel == 0 {
-            num_cpus::get()
-        } else {
-            self.settings.max_parallel
-        };
-
+    async fn execute_parallel(
+        &self,
+        tasks: &[&Task],
+        max_failures: usize,
+    ) -> Result<Vec<TaskResult>> {
+        let max_parallel = self.max_parallel();
         let semaphore = Arc::new(Semaphore::new(max_parallel));
-        let failed = Arc::new(AtomicBool::new(false));
+        let failures = Arc::new(AtomicUsize::new(0));
+        let stopped = || Self::limit_reached(failures.load(Ordering::SeqCst), max_failures);
         let mut handles = Vec::with_capacity(tasks.len());
 
         for task in tasks {
             let files = self.filter_files(task);
 
             // Skip if no matching files and glob is specified
-            if task.glob.is_some() && files.is_empty() {
+            if task.filters_files() && files.is_empty() {
                 continue;
             }
 
-            // Check fail_fast before spawning new tasks
-            if fail_fast && failed.load(Ordering::SeqCst) {
+            // Stop spawning new tasks once enough tasks failed, counting those that
+            // finished while we waited for a permit
+            let permit = semaphore.clone().acquire_owned().await?;
+            if stopped() {
                 break;
             }
-
-            let permit = semaphore.clone().acquire_owned().await?;
             let task_clone = (*task).clone();
             let files_clone = files.clone();
-            let failed_clone = failed.clone();
-            let settings_clone = self.settings.clone();
-            let hook_args_clone = self.hook_args.clone();
+            let failures_clone = failures.clone();
+            let executor = self.for_task();
 
             let handle = tokio::spawn(async move {
-                let executor = TaskExecutor {
-                    settings: settings_clone,
-                    staged_files: files_clone.clone(),
-                    current_branch: None,
-                    hook_args: hook_args_clone,
-                };
-
                 let result = executor.execute_task(&task_clone, &files_clone).await;
-                drop(permit);
 
+                // Count the failure before another task can take the permit
                 if let Ok(ref res) = result {
                     if !res.success && !task_clone.allow_failure {
-                        failed_clone.store(true, Ordering::SeqCst);
+                        failures_clone.fetch_add(1, Ordering::SeqCst);
                     }
                 }
+                drop(permit);
 
                 result
             });
@@ -427,76 +917,319 @@ impl TaskExecutor {
         Ok(results)
     }
 
-    /// Execute a single task
+    /// Execute a single task, once per commit for `per_commit` tasks
     async fn execute_task(&self, task: &Task, files: &[PathBuf]) -> Result<TaskResult> {
+        match &self.commits {
+            Some(commits) if task.per_commit => self.execute_per_commit(task, files, commits).await,
+            _ => self.execute_command(task, files, None).await,
+        }
+    }
+
+    /// Run a task for each commit in order, stopping at the first commit it fails on
+    async fn execute_per_commit(
+        &self,
+        task: &Task,
+        files: &[PathBuf],
+        commits: &[String],
+    ) -> Result<TaskResult> {
+        let start = Instant::now();
+        let mut stdout = String::new();
+        let mut stderr = String::new();
+        let mut command = String::new();
+        let mut failure = None;
+
+        for sha in commits {
+            let result = self.execute_command(task, files, Some(sha)).await?;
+            stdout.push_str(&result.stdout);
+            stderr.push_str(&result.stderr);
+            command = result.command;
+            if !result.success {
+                failure = Some((result.exit_code, sha));
+                break;
+            }
+        }
+
+        let duration_ms = start.elapsed().as_millis() as u64;
+        let result = match failure {
+            Some((exit_code, sha)) => {
+                let stderr = format!("{}\n(failed at commit {})", stderr.trim_end(), sha);
+                TaskResult::failure(task.name.clone(), exit_code, stdout, stderr, duration_ms)
+            }
+            None => TaskResult::success(task.name.clone(), stdout, stderr, duration_ms),
+        };
+        let result = result.with_command(command);
+        Ok(if task.filters_files() {
+            result.with_file_count(files.len())
+        } else {
+            result
+        })
+    }
+
+    /// Run a task's command once, with `{sha}` set to `sha` when given
+    async fn execute_command(
+        &self,
+        task: &Task,
+        files: &[PathBuf],
+        sha: Option<&str>,
+    ) -> Result<TaskResult> {
         let start = Instant::now();
 
         // Build the command
-        let command = self.build_command(task, files);
+        let mut command = self.build_command(task, files);
+        if let Some(sha) = sha {
+            command = command.replace("{sha}", sha);
+        }
+        let cwd = self.task_cwd(task);
+
+        let stdin = if task.inherit_stdin || task.interactive {
+            Stdio::inherit()
+        } else if task.stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        };
+
+        let env = self.task_env(task, files);
+        if let Some(dir) = &self.dump_env_dir {
+            Self::dump_env(dir, task, &env)?;
+        }
 
-        let output = Command::new(self.get_shell())
+        let mut child = Command::new(self.get_shell())
             .arg(self.get_shell_arg())
             .arg(&command)
-            .current_dir(task.cwd.as_deref().unwrap_or("."))
-            .envs(&task.env)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .await
+            .current_dir(&cwd)
+            .envs(&env)
+            .stdin(stdin)
+            .stdout(Self::output_stdio(task))
+            .stderr(Self::output_stdio(task))
+            .spawn()
             .with_context(|| format!("Failed to execute task: {}", task.name))?;
 
+        // Feed stdin concurrently so a chatty child can't deadlock on a full pipe
+        if let (Some(input), Some(mut child_stdin)) = (task.stdin.clone(), child.stdin.take()) {
+            tokio::spawn(async move {
+                let _ = child_stdin.write_all(input.as_bytes()).await;
+            });
+        }
+
+        let wait = child.wait_with_output();
+        let output = match task.warn_after_ms {
+            Some(ms) if ms > 0 => {
+                Self::warn_while_running(wait, Duration::from_millis(ms), |elapsed| {
+                    eprintln!(
+                        "  {} Task '{}' still running after {}...",
+                        "⏳".yellow(),
+                        task.name,
+                        ExecutionStats::format_duration(elapsed.as_millis() as u64)
+                    );
+                })
+                .await
+            }
+            _ => wait.await,
+        }
+        .with_context(|| format!("Failed to execute task: {}", task.name))?;
+
+        // Tee stdout into the task's output file (relative to its cwd)
+        if let Some(output_file) = &task.output_file {
+            let path =
+                Path::new(&cwd).join(expand_vars(&self.expand_git_placeholders(output_file)));
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create {}", parent.display()))?;
+            }
+            std::fs::write(&path, &output.stdout).with_context(|| {
+                format!(
+                    "Failed to write output of task '{}' to {}",
+                    task.name,
+                    path.display()
+                )
+            })?;
+        }
+
         let duration_ms = start.elapsed().as_millis() as u64;
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
 
-        if output.status.success() {
-            Ok(TaskResult::success(
-                task.name.clone(),
-                stdout,
-                stderr,
-                duration_ms,
-            ))
+        let accepted = output
+            .status
+            .code()
+            .is_some_and(|code| task.success_codes.contains(&code));
+
+        let result = if accepted && task.fail_on_stderr && !stderr.trim().is_empty() {
+            let exit_code = output.status.code().unwrap_or_default();
+            let stderr = format!(
+                "{}\n(failed because fail_on_stderr is set)",
+                stderr.trim_end()
+            );
+            TaskResult::failure(task.name.clone(), exit_code, stdout, stderr, duration_ms)
+        } else if accepted {
+            TaskResult::success(task.name.clone(), stdout, stderr, duration_ms)
         } else {
             let exit_code = output.status.code().unwrap_or(-1);
-            Ok(TaskResult::failure(
-                task.name.clone(),
-                exit_code,
-                stdout,
-                stderr,
-                duration_ms,
-            ))
-        }
-    }
+            TaskResult::failure(task.name.clone(), exit_code, stdout, stderr, duration_ms)
+        };
 
-    /// Build the command string with file and argument substitution
-    fn build_command(&self, task: &Task, files: &[PathBuf]) -> String {
-        let files_str: String = files
-            .iter()
-            .map(|f| {
-                let path = f.to_string_lossy();
-                if path.contains(' ') {
-                    format!("\"{}\"", path)
-                } else {
-                    path.to_string()
-                }
-            })
-            .collect::<Vec<_>>()
-            .join(" ");
+        // Keep output only where it helps: for failures
+        let quiet = task
+            .quiet_on_success
+            .unwrap_or(self.settings.quiet_on_success);
+        let result = if quiet && result.success {
+            result.without_output()
+        } else {
+            result
+        };
 
-        let mut command = task.run.clone();
+        let result = result.with_command(command);
+        Ok(if task.filters_files() {
+            result.with_file_count(files.len())
+        } else {
+            result
+        })
+    }
 
-        // Replace {files} placeholder with actual files
-        if command.contains("{files}") {
-            command = command.replace("{files}", &files_str);
-        } else if task.glob.is_some() && !files.is_empty() {
-            // Append files to command if glob is specified
-            command = format!("{} {}", command, files_str);
+    /// Await `future`, calling `warn` with the elapsed time every `period` until it completes
+    async fn warn_while_running<F: Future>(
+        future: F,
+        period: Duration,
+        mut warn: impl FnMut(Duration),
+    ) -> F::Output {
+        let start = tokio::time::Instant::now();
+        let mut interval = tokio::time::interval_at(start + period, period);
+        tokio::pin!(future);
+        loop {
+            tokio::select! {
+                output = &mut future => return output,
+                _ = interval.tick() => warn(start.elapsed()),
+            }
         }
+    }
 
-        // Replace hook argument placeholders: $1, $2, $3, etc.
-        for (i, arg) in self.hook_args.iter().enumerate() {
-            let placeholder = format!("${}", i + 1);
-            command = command.replace(&placeholder, arg);
+    /// Stdout/stderr handle: interactive tasks write straight to the terminal
+    fn output_stdio(task: &Task) -> Stdio {
+        if task.interactive {
+            Stdio::inherit()
+        } else {
+            Stdio::piped()
+        }
+    }
+
+    /// Substitute `{git.branch}` and `{git.sha}` placeholders
+    fn expand_git_placeholders(&self, value: &str) -> String {
+        let branch = self.current_branch.as_deref().unwrap_or_default();
+        let sha = self.current_sha.as_deref().unwrap_or_default();
+        value
+            .replace("{git.branch}", branch)
+            .replace("{git.sha}", sha)
+    }
+
+    /// Substitute git placeholders in task env values
+    fn resolve_env(&self, env: &HashMap<String, String>) -> HashMap<String, String> {
+        env.iter()
+            .map(|(key, value)| (key.clone(), self.expand_git_placeholders(value)))
+            .collect()
+    }
+
+    /// Variables set for a task on top of the inherited environment
+    fn task_env(&self, task: &Task, files: &[PathBuf]) -> HashMap<String, String> {
+        let mut env = self.resolve_env(&task.env);
+        if let Some(name) = &task.files_env {
+            env.insert(name.clone(), Self::files_list(files));
+        }
+        env
+    }
+
+    /// Write the inherited environment overlaid with the task's own variables to
+    /// `<dir>/<task>.env`, as sorted `KEY='value'` lines that a shell can source
+    fn dump_env(dir: &Path, task: &Task, env: &HashMap<String, String>) -> Result<()> {
+        let mut merged: BTreeMap<String, String> = std::env::vars().collect();
+        merged.extend(env.iter().map(|(k, v)| (k.clone(), v.clone())));
+        let content: String = merged
+            .iter()
+            .map(|(key, value)| format!("{}='{}'\n", key, value.replace('\'', "'\\''")))
+            .collect();
+
+        let file_name: String = task
+            .name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let path = dir.join(format!("{}.env", file_name));
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write environment to {}", path.display()))
+    }
+
+    /// Matched files for `files_env`, one path per line
+    fn files_list(files: &[PathBuf]) -> String {
+        files
+            .iter()
+            .map(|f| f.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Build the command string with file and argument substitution
+    fn build_command(&self, task: &Task, files: &[PathBuf]) -> String {
+        let commands = self.effective_run(task).unwrap_or_default();
+        let uses_dirs = commands.iter().any(|c| c.contains("{dirs}"));
+        let uses_files = commands.iter().any(|c| c.contains("{files}"));
+
+        let mut commands: Vec<String> = commands
+            .iter()
+            .map(|command| self.substitute(task, command, files, uses_dirs))
+            .collect();
+
+        // Files and passthrough arguments go after the last command of a sequence
+        if let Some(command) = commands.last_mut() {
+            if !uses_files
+                && task.filters_files()
+                && !files.is_empty()
+                && task.files_env.is_none()
+                && !uses_dirs
+            {
+                // Append files to command if glob is specified (unless passed via files_env or {dirs})
+                *command = format!("{} {}", command, Self::quote_paths(files));
+            }
+
+            // Append passthrough arguments last so they aren't treated as placeholders
+            for arg in &self.passthrough_args {
+                *command = format!("{} {}", command, Self::quote_arg(arg));
+            }
+        }
+
+        TaskCommand::join(&commands)
+    }
+
+    /// Substitute file and hook argument placeholders in one command
+    fn substitute(&self, task: &Task, command: &str, files: &[PathBuf], uses_dirs: bool) -> String {
+        let mut command = command.to_string();
+
+        // Replace {dirs} with the distinct top-level directories of the task's files
+        if uses_dirs {
+            let files = if task.filters_files() {
+                files
+            } else {
+                &self.staged_files
+            };
+            command = command.replace("{dirs}", &Self::quote_paths(&Self::changed_dirs(files)));
+        }
+
+        // Replace {files} placeholder with actual files
+        if command.contains("{files}") {
+            command = command.replace("{files}", &Self::quote_paths(files));
+        }
+
+        // Replace hook argument placeholders: $1, $2, $3, etc.
+        for (i, arg) in self.hook_args.iter().enumerate() {
+            let placeholder = format!("${}", i + 1);
+            command = command.replace(&placeholder, arg);
         }
 
         // Also support {1}, {2}, {3} style placeholders
@@ -508,67 +1241,145 @@ impl TaskExecutor {
         command
     }
 
+    /// Join paths with spaces, quoting those that contain a space
+    fn quote_paths(paths: &[PathBuf]) -> String {
+        paths
+            .iter()
+            .map(|f| Self::quote_arg(&f.to_string_lossy()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Quote a single argument if it contains a space
+    fn quote_arg(arg: &str) -> String {
+        if arg.contains(' ') {
+            format!("\"{}\"", arg)
+        } else {
+            arg.to_string()
+        }
+    }
+
+    /// Distinct top-level directories of the given files, sorted (`.` for root files)
+    fn changed_dirs(files: &[PathBuf]) -> Vec<PathBuf> {
+        let dirs: BTreeSet<PathBuf> = files
+            .iter()
+            .map(|f| {
+                let mut components = f.components();
+                match (components.next(), components.next()) {
+                    (Some(first), Some(_)) => PathBuf::from(first.as_os_str()),
+                    _ => PathBuf::from("."),
+                }
+            })
+            .collect();
+        dirs.into_iter().collect()
+    }
+
     /// Filter staged files based on task glob pattern (supports negation with !)
+    /// and/or regex; a file must match both when both are set
     fn filter_files(&self, task: &Task) -> Vec<PathBuf> {
-        let Some(glob_pattern) = &task.glob else {
+        let files = self.matched_files(task);
+        if task.skip_if_only_deletions && self.only_deletions(&files) {
             return Vec::new();
-        };
+        }
+        files
+    }
 
-        // Parse multiple patterns (comma or space separated)
-        let patterns: Vec<&str> = glob_pattern
-            .split([',', ' '])
-            .map(|s| s.trim())
-            .filter(|s| !s.is_empty())
-            .collect();
+    /// Whether every file is a staged deletion (files with unknown status are not)
+    fn only_deletions(&self, files: &[PathBuf]) -> bool {
+        files
+            .iter()
+            .all(|f| self.file_statuses.get(f) == Some(&FileStatus::Deleted))
+    }
+
+    /// Files matching the task's glob, regex and status filters
+    fn matched_files(&self, task: &Task) -> Vec<PathBuf> {
+        if !task.filters_files() {
+            return Vec::new();
+        }
 
         let mut include_patterns: Vec<Pattern> = Vec::new();
         let mut exclude_patterns: Vec<Pattern> = Vec::new();
 
-        for pat in patterns {
-            if let Some(negated) = pat.strip_prefix('!') {
-                if let Ok(p) = Pattern::new(negated) {
-                    exclude_patterns.push(p);
+        if let Some(glob_pattern) = &task.glob {
+            // Parse multiple patterns (comma or space separated)
+            let patterns = glob_pattern
+                .split([',', ' '])
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty());
+
+            for pat in patterns {
+                if let Some(negated) = pat.strip_prefix('!') {
+                    if let Ok(p) = Pattern::new(negated) {
+                        exclude_patterns.push(p);
+                    }
+                } else if let Ok(p) = Pattern::new(pat) {
+                    include_patterns.push(p);
                 }
-            } else if let Ok(p) = Pattern::new(pat) {
-                include_patterns.push(p);
             }
-        }
 
-        // If no include patterns, nothing matches
-        if include_patterns.is_empty() {
-            return Vec::new();
+            // If no include patterns, nothing matches
+            if include_patterns.is_empty() {
+                return Vec::new();
+            }
         }
 
-        self.staged_files
-            .iter()
-            .filter(|f| {
-                let path_str = f.to_string_lossy();
-                let filename = f.file_name().and_then(|n| n.to_str()).unwrap_or("");
-
-                // Check if file matches any include pattern
-                let included = include_patterns.iter().any(|p| {
-                    p.matches(&path_str)
-                        || p.matches(filename)
-                        || p.matches(&path_str.replace('\\', "/"))
-                });
+        let regex = match task.regex.as_deref().map(regex::Regex::new) {
+            Some(Ok(re)) => Some(re),
+            Some(Err(_)) => return Vec::new(),
+            None => None,
+        };
 
-                if !included {
-                    return false;
-                }
+        let options = MatchOptions {
+            case_sensitive: !self.settings.case_insensitive_globs,
+            ..MatchOptions::new()
+        };
 
-                // Check if file matches any exclude pattern
-                let excluded = exclude_patterns.iter().any(|p| {
-                    p.matches(&path_str)
-                        || p.matches(filename)
-                        || p.matches(&path_str.replace('\\', "/"))
-                });
+        // Files whose status is unknown (e.g. passed via --files) never match a status filter
+        let wanted_status = task.file_status.as_deref().map(FileStatus::from_str);
 
-                !excluded
+        self.staged_files
+            .iter()
+            .filter(|f| {
+                task.glob.is_none()
+                    || (include_patterns
+                        .iter()
+                        .any(|p| Self::matches_path(p, f, options))
+                        && !exclude_patterns
+                            .iter()
+                            .any(|p| Self::matches_path(p, f, options)))
+            })
+            .filter(|f| {
+                regex.as_ref().map_or(true, |re| {
+                    re.is_match(&f.to_string_lossy().replace('\\', "/"))
+                })
+            })
+            .filter(|f| match wanted_status {
+                None => true,
+                Some(wanted) => wanted.is_some() && self.file_statuses.get(*f).copied() == wanted,
             })
             .cloned()
             .collect()
     }
 
+    /// Match a glob against a staged file path.
+    ///
+    /// Patterns containing `/` are anchored to the full repository-relative path
+    /// (`tests/foo.rs` only matches at the root, `**/foo.rs` at any depth).
+    /// Patterns without `/` also match the file name, so `*.test.js` excludes
+    /// test files in every directory. Includes and excludes share these rules.
+    fn matches_path(pattern: &Pattern, path: &Path, options: MatchOptions) -> bool {
+        let normalized = path.to_string_lossy().replace('\\', "/");
+        if pattern.matches_with(&normalized, options) {
+            return true;
+        }
+
+        !pattern.as_str().contains('/')
+            && path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|name| pattern.matches_with(name, options))
+    }
+
     /// Get the appropriate shell for the current platform
     fn get_shell(&self) -> &'static str {
         if cfg!(windows) {
@@ -612,8 +1423,16 @@ mod tests {
                 PathBuf::from("src/utils/helper.ts"),
                 PathBuf::from("src/components/Button.tsx"),
             ],
+            file_statuses: HashMap::new(),
             current_branch: Some("main".to_string()),
+            current_sha: Some("0123456789abcdef0123456789abcdef01234567".to_string()),
             hook_args: vec!["arg1".to_string(), "arg2".to_string()],
+            passthrough_args: Vec::new(),
+            shuffle_seed: None,
+            work_dir: None,
+            dump_env_dir: None,
+            commits: None,
+            max_failures: None,
         }
     }
 
@@ -622,14 +1441,9 @@ mod tests {
         let executor = create_test_executor();
         let task = Task {
             name: "test".to_string(),
-            run: "echo".to_string(),
+            run: "echo".into(),
             glob: Some("*.rs".to_string()),
-            staged: true,
-            cwd: None,
-            env: HashMap::new(),
-            allow_failure: false,
-            condition: None,
-            depends_on: vec![],
+            ..Default::default()
         };
 
         let files = executor.filter_files(&task);
@@ -641,33 +1455,194 @@ mod tests {
         let executor = create_test_executor();
         let task = Task {
             name: "test".to_string(),
-            run: "echo".to_string(),
+            run: "echo".into(),
             glob: Some("*.rs, !tests/*.rs".to_string()),
-            staged: true,
-            cwd: None,
-            env: HashMap::new(),
-            allow_failure: false,
-            condition: None,
-            depends_on: vec![],
+            ..Default::default()
         };
 
         let files = executor.filter_files(&task);
         assert_eq!(files.len(), 2); // main.rs, lib.rs (excluding tests/test.rs)
     }
 
+    #[test]
+    fn test_filter_files_anchored_exclusion() {
+        let mut executor = create_test_executor();
+        executor.staged_files = vec![
+            PathBuf::from("tests/foo.rs"),
+            PathBuf::from("src/tests/foo.rs"),
+            PathBuf::from("src/foo.rs"),
+        ];
+        let mut task = Task {
+            name: "test".to_string(),
+            run: "echo".into(),
+            glob: Some("*.rs, !tests/foo.rs".to_string()),
+            ..Default::default()
+        };
+
+        // Anchored to the repository root: only the top-level tests/foo.rs is excluded
+        let files = executor.filter_files(&task);
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from("src/tests/foo.rs"),
+                PathBuf::from("src/foo.rs")
+            ]
+        );
+
+        // `**/` excludes the file at any depth
+        task.glob = Some("*.rs, !**/foo.rs".to_string());
+        assert!(executor.filter_files(&task).is_empty());
+
+        // A bare file name pattern matches in every directory
+        task.glob = Some("*.rs, !foo.rs".to_string());
+        assert!(executor.filter_files(&task).is_empty());
+    }
+
+    #[test]
+    fn test_with_files_dedupes_paths() {
+        let executor = TaskExecutor::with_files(
+            Settings::default(),
+            vec![
+                PathBuf::from("src/main.rs"),
+                PathBuf::from("./src/main.rs"),
+                PathBuf::from("src\\lib.rs"),
+                PathBuf::from("README.md"),
+                PathBuf::from("src/lib.rs"),
+            ],
+        )
+        .unwrap();
+        let task = Task {
+            name: "lint".to_string(),
+            run: "lint".into(),
+            glob: Some("*.rs".to_string()),
+            ..Default::default()
+        };
+
+        let files = executor.filter_files(&task);
+        assert_eq!(
+            files,
+            vec![PathBuf::from("src/main.rs"), PathBuf::from("src/lib.rs")]
+        );
+        assert_eq!(
+            executor.build_command(&task, &files),
+            "lint src/main.rs src/lib.rs"
+        );
+    }
+
+    #[test]
+    fn test_filter_files_by_status() {
+        let mut executor = create_test_executor();
+        executor.file_statuses = HashMap::from([
+            (PathBuf::from("src/main.rs"), FileStatus::Modified),
+            (PathBuf::from("src/lib.rs"), FileStatus::Added),
+            (PathBuf::from("tests/test.rs"), FileStatus::Renamed),
+        ]);
+        let mut task = Task {
+            name: "license".to_string(),
+            run: "add-license".into(),
+            glob: Some("*.rs".to_string()),
+            file_status: Some("added".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            executor.filter_files(&task),
+            vec![PathBuf::from("src/lib.rs")]
+        );
+
+        task.file_status = Some("modified".to_string());
+        assert_eq!(
+            executor.filter_files(&task),
+            vec![PathBuf::from("src/main.rs")]
+        );
+
+        task.file_status = None;
+        assert_eq!(executor.filter_files(&task).len(), 3);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_skip_if_only_deletions() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let raw = git2::Repository::init(root).unwrap();
+        std::fs::write(root.join("old.rs"), "fn old() {}\n").unwrap();
+        let mut index = raw.index().unwrap();
+        index.add_path(Path::new("old.rs")).unwrap();
+        index.write().unwrap();
+        let tree = raw.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        raw.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+            .unwrap();
+
+        // Stage only the deletion of old.rs
+        std::fs::remove_file(root.join("old.rs")).unwrap();
+        index.remove_path(Path::new("old.rs")).unwrap();
+        index.write().unwrap();
+
+        let repo = GitRepository::open(root).unwrap();
+        let executor = TaskExecutor::from_repo(Settings::default(), &repo);
+        let mut task = Task {
+            name: "lint".to_string(),
+            run: "true".into(),
+            glob: Some("*.rs".to_string()),
+            skip_if_only_deletions: true,
+            ..Default::default()
+        };
+        let hook = Hook {
+            tasks: vec![task.clone()],
+            ..Default::default()
+        };
+
+        let result = executor.execute_hook(&hook).await.unwrap();
+        assert!(result.tasks.is_empty());
+        assert_eq!(result.skipped.len(), 1);
+        assert!(result.skipped[0].reason.starts_with("only deletions"));
+
+        // Without the option the deleted path is still passed to the task
+        task.skip_if_only_deletions = false;
+        assert_eq!(executor.filter_files(&task), vec![PathBuf::from("old.rs")]);
+    }
+
+    #[test]
+    fn test_filter_files_regex() {
+        let mut executor = create_test_executor();
+        executor
+            .staged_files
+            .push(PathBuf::from("src/__tests__/Button.test.tsx"));
+        let mut task = Task {
+            name: "typecheck".to_string(),
+            run: "tsc".into(),
+            regex: Some(r"^src/.*\.tsx?$".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            executor.filter_files(&task),
+            vec![
+                PathBuf::from("src/utils/helper.ts"),
+                PathBuf::from("src/components/Button.tsx"),
+                PathBuf::from("src/__tests__/Button.test.tsx"),
+            ]
+        );
+
+        // Combined with a glob, files must match both
+        task.glob = Some("*.tsx".to_string());
+        task.regex = Some("^[^_]*$".to_string());
+        assert_eq!(
+            executor.filter_files(&task),
+            vec![PathBuf::from("src/components/Button.tsx")]
+        );
+    }
+
     #[test]
     fn test_filter_files_multiple_extensions() {
         let executor = create_test_executor();
         let task = Task {
             name: "test".to_string(),
-            run: "echo".to_string(),
+            run: "echo".into(),
             glob: Some("*.ts, *.tsx".to_string()),
-            staged: true,
-            cwd: None,
-            env: HashMap::new(),
-            allow_failure: false,
-            condition: None,
-            depends_on: vec![],
+            ..Default::default()
         };
 
         let files = executor.filter_files(&task);
@@ -679,14 +1654,9 @@ mod tests {
         let executor = create_test_executor();
         let mut task = Task {
             name: "test".to_string(),
-            run: "echo".to_string(),
-            glob: None,
-            staged: true,
-            cwd: None,
-            env: HashMap::new(),
-            allow_failure: false,
+            run: "echo".into(),
             condition: Some("branch == main".to_string()),
-            depends_on: vec![],
+            ..Default::default()
         };
 
         assert!(executor.evaluate_condition(&task));
@@ -695,19 +1665,103 @@ mod tests {
         assert!(!executor.evaluate_condition(&task));
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_unreadable_index_runs_without_staged_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        git2::Repository::init(temp_dir.path()).unwrap();
+        std::fs::write(temp_dir.path().join(".git/index"), "not an index").unwrap();
+        let repo = GitRepository::open(temp_dir.path()).unwrap();
+        assert!(repo.staged_entries().is_err());
+
+        let executor = TaskExecutor::from_repo(Settings::default(), &repo);
+        let hook = Hook {
+            tasks: vec![
+                Task {
+                    name: "lint".to_string(),
+                    run: "true".into(),
+                    glob: Some("*.rs".to_string()),
+                    ..Default::default()
+                },
+                Task {
+                    name: "test".to_string(),
+                    run: "true".into(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let result = executor.execute_hook(&hook).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.tasks.len(), 1);
+        assert_eq!(result.tasks[0].name, "test");
+        assert_eq!(result.skipped[0].name, "lint");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_protected_branch_ignores_conditions() {
+        let mut executor = create_test_executor();
+        executor.current_branch = Some("release/1.2".to_string());
+        let hook = Hook {
+            tasks: vec![Task {
+                name: "e2e".to_string(),
+                run: "true".into(),
+                condition: Some("env:FASTHOOKS_TEST_NEVER_SET".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let result = executor.execute_hook(&hook).await.unwrap();
+        assert!(result.tasks.is_empty());
+
+        executor.settings.protected_branches = vec!["main".to_string(), "release/*".to_string()];
+        let result = executor.execute_hook(&hook).await.unwrap();
+        assert_eq!(result.tasks.len(), 1);
+        assert!(result.skipped.is_empty());
+    }
+
+    #[test]
+    fn test_worker_count_per_strategy() {
+        use ConcurrencyStrategy::*;
+        assert_eq!(TaskExecutor::worker_count(Cpu, 0, 8), 8);
+        assert_eq!(TaskExecutor::worker_count(Io, 0, 8), 32);
+        assert_eq!(TaskExecutor::worker_count(Fixed, 0, 8), 1);
+        assert_eq!(TaskExecutor::worker_count(Fixed, 3, 8), 3);
+        // An explicit limit (e.g. from --max-parallel) overrides the strategy
+        assert_eq!(TaskExecutor::worker_count(Io, 2, 8), 2);
+    }
+
+    #[test]
+    fn test_hook_condition_skips_on_other_branches() {
+        let hook = Hook {
+            condition: Some("branch == main".to_string()),
+            tasks: vec![Task {
+                name: "deploy-check".to_string(),
+                run: "echo".into(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        assert!(TaskExecutor::hook_enabled(&hook, Some("main")));
+        assert!(!TaskExecutor::hook_enabled(&hook, Some("feature/login")));
+        assert!(TaskExecutor::hook_enabled(
+            &Hook::default(),
+            Some("feature/login")
+        ));
+    }
+
     #[test]
     fn test_evaluate_condition_branch_not_equals() {
         let executor = create_test_executor();
         let mut task = Task {
             name: "test".to_string(),
-            run: "echo".to_string(),
-            glob: None,
-            staged: true,
-            cwd: None,
-            env: HashMap::new(),
-            allow_failure: false,
+            run: "echo".into(),
             condition: Some("branch != main".to_string()),
-            depends_on: vec![],
+            ..Default::default()
         };
 
         assert!(!executor.evaluate_condition(&task));
@@ -721,14 +1775,9 @@ mod tests {
         let executor = create_test_executor();
         let task = Task {
             name: "test".to_string(),
-            run: "echo".to_string(),
-            glob: None,
-            staged: true,
-            cwd: None,
-            env: HashMap::new(),
-            allow_failure: false,
+            run: "echo".into(),
             condition: Some("env:PATH".to_string()),
-            depends_on: vec![],
+            ..Default::default()
         };
 
         assert!(executor.evaluate_condition(&task)); // PATH should exist
@@ -739,33 +1788,56 @@ mod tests {
         let executor = create_test_executor();
         let task = Task {
             name: "test".to_string(),
-            run: "commitlint --edit $1".to_string(),
-            glob: None,
-            staged: true,
-            cwd: None,
-            env: HashMap::new(),
-            allow_failure: false,
-            condition: None,
-            depends_on: vec![],
+            run: "commitlint --edit $1".into(),
+            ..Default::default()
         };
 
         let command = executor.build_command(&task, &[]);
         assert_eq!(command, "commitlint --edit arg1");
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_passthrough_args_appended_to_every_task() {
+        let executor = create_test_executor()
+            .with_passthrough_args(vec!["--verbose".to_string(), "a b".to_string()]);
+        let hook = Hook {
+            tasks: vec![
+                Task {
+                    name: "plain".to_string(),
+                    run: "echo plain".into(),
+                    ..Default::default()
+                },
+                Task {
+                    name: "globbed".to_string(),
+                    run: "echo $1".into(),
+                    glob: Some("*.md".to_string()),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let result = executor.execute_hook(&hook).await.unwrap();
+        let commands: HashMap<&str, &str> = result
+            .tasks
+            .iter()
+            .map(|t| (t.name.as_str(), t.command.as_str()))
+            .collect();
+        assert_eq!(commands["plain"], "echo plain --verbose \"a b\"");
+        assert_eq!(commands["globbed"], "echo arg1 README.md --verbose \"a b\"");
+        let plain = result.tasks.iter().find(|t| t.name == "plain").unwrap();
+        assert_eq!(plain.stdout.trim_end(), "plain --verbose a b");
+    }
+
     #[test]
     fn test_build_command_with_files() {
         let executor = create_test_executor();
         let task = Task {
             name: "test".to_string(),
-            run: "eslint {files}".to_string(),
+            run: "eslint {files}".into(),
             glob: Some("*.rs".to_string()),
-            staged: true,
-            cwd: None,
-            env: HashMap::new(),
-            allow_failure: false,
-            condition: None,
-            depends_on: vec![],
+            ..Default::default()
         };
 
         let files = vec![PathBuf::from("src/main.rs"), PathBuf::from("src/lib.rs")];
@@ -776,70 +1848,1109 @@ mod tests {
 
     #[test]
     fn test_sort_tasks_by_dependencies() {
-        let executor = create_test_executor();
         let tasks = vec![
             Task {
                 name: "test".to_string(),
-                run: "cargo test".to_string(),
-                glob: None,
-                staged: true,
-                cwd: None,
-                env: HashMap::new(),
-                allow_failure: false,
-                condition: None,
+                run: "cargo test".into(),
                 depends_on: vec!["lint".to_string()],
+                ..Default::default()
             },
             Task {
                 name: "lint".to_string(),
-                run: "cargo clippy".to_string(),
-                glob: None,
-                staged: true,
-                cwd: None,
-                env: HashMap::new(),
-                allow_failure: false,
-                condition: None,
-                depends_on: vec![],
+                run: "cargo clippy".into(),
+                ..Default::default()
             },
         ];
 
-        let sorted = executor.sort_tasks_by_dependencies(&tasks).unwrap();
+        let sorted = TaskExecutor::sort_tasks_by_dependencies(&tasks).unwrap();
         assert_eq!(sorted[0].name, "lint");
         assert_eq!(sorted[1].name, "test");
     }
 
     #[test]
     fn test_circular_dependency_detection() {
-        let executor = create_test_executor();
         let tasks = vec![
             Task {
                 name: "a".to_string(),
-                run: "echo a".to_string(),
-                glob: None,
-                staged: true,
-                cwd: None,
-                env: HashMap::new(),
-                allow_failure: false,
-                condition: None,
+                run: "echo a".into(),
                 depends_on: vec!["b".to_string()],
+                ..Default::default()
             },
             Task {
                 name: "b".to_string(),
-                run: "echo b".to_string(),
-                glob: None,
-                staged: true,
-                cwd: None,
-                env: HashMap::new(),
-                allow_failure: false,
-                condition: None,
+                run: "echo b".into(),
                 depends_on: vec!["a".to_string()],
+                ..Default::default()
             },
         ];
 
-        let result = executor.sort_tasks_by_dependencies(&tasks);
+        let result = TaskExecutor::sort_tasks_by_dependencies(&tasks);
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
             .to_string()
             .contains("Circular dependency"));
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_max_parallel_one_serializes_execution() {
+        let mut executor = create_test_executor();
+        executor.settings.max_parallel = 1;
+
+        let tasks: Vec<Task> = ["first", "second"]
+            .iter()
+            .map(|name| Task {
+                name: name.to_string(),
+                run: "sleep 0.2".into(),
+                ..Default::default()
+            })
+            .collect();
+        let task_refs: Vec<&Task> = tasks.iter().collect();
+
+        let start = Instant::now();
+        let results = executor.execute_parallel(&task_refs, 0).await.unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert!(start.elapsed().as_millis() >= 400);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_per_commit_task_runs_once_per_rebased_commit() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let raw = git2::Repository::init(root).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        let tree = raw
+            .find_tree(raw.treebuilder(None).unwrap().write().unwrap())
+            .unwrap();
+        let mut parent = raw
+            .find_commit(
+                raw.commit(Some("HEAD"), &sig, &sig, "base", &tree, &[])
+                    .unwrap(),
+            )
+            .unwrap();
+        raw.branch("upstream", &parent, false).unwrap();
+        for message in ["one", "two", "three"] {
+            let id = raw
+                .commit(Some("HEAD"), &sig, &sig, message, &tree, &[&parent])
+                .unwrap();
+            parent = raw.find_commit(id).unwrap();
+        }
+
+        let commits = GitRepository::open(root)
+            .unwrap()
+            .commits_between("upstream", None)
+            .unwrap();
+        assert_eq!(commits.len(), 3);
+
+        let executor = create_test_executor()
+            .with_commits(commits.clone())
+            .in_dir(root);
+        let hook = Hook {
+            parallel: Some(false),
+            tasks: vec![
+                Task {
+                    name: "check".to_string(),
+                    run: "echo {sha} >> checked.txt".into(),
+                    per_commit: true,
+                    ..Default::default()
+                },
+                Task {
+                    name: "once".to_string(),
+                    run: "echo once >> once.txt".into(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let result = executor.execute_hook(&hook).await.unwrap();
+        assert!(result.success);
+
+        let checked = std::fs::read_to_string(root.join("checked.txt")).unwrap();
+        assert_eq!(checked.lines().collect::<Vec<_>>(), commits);
+        let once = std::fs::read_to_string(root.join("once.txt")).unwrap();
+        assert_eq!(once.lines().count(), 1);
+
+        // Without a commit range the task is skipped, not run against HEAD
+        let result = create_test_executor()
+            .in_dir(root)
+            .execute_hook(&hook)
+            .await
+            .unwrap();
+        assert_eq!(result.skipped[0].name, "check");
+    }
+
+    #[tokio::test]
+    async fn test_warn_while_running_warns_until_done() {
+        let mut warnings = Vec::new();
+        let output = TaskExecutor::warn_while_running(
+            async {
+                tokio::time::sleep(Duration::from_millis(350)).await;
+                "done"
+            },
+            Duration::from_millis(100),
+            |elapsed| warnings.push(elapsed),
+        )
+        .await;
+
+        assert_eq!(output, "done");
+        assert!((2..=3).contains(&warnings.len()), "{:?}", warnings);
+        assert!(warnings[0] >= Duration::from_millis(100));
+
+        // A future that finishes first is never warned about
+        let mut warned = false;
+        TaskExecutor::warn_while_running(async {}, Duration::from_millis(100), |_| warned = true)
+            .await;
+        assert!(!warned);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_warn_after_task_still_completes() {
+        let executor = create_test_executor();
+        let task = Task {
+            name: "slow".to_string(),
+            run: "sleep 0.25 && echo finished".into(),
+            warn_after_ms: Some(100),
+            ..Default::default()
+        };
+
+        let result = executor.execute_task(&task, &[]).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.stdout.trim(), "finished");
+        assert!(result.duration_ms >= 250);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_max_failures_stops_after_nth_failure() {
+        let mut executor = create_test_executor();
+        executor.settings.max_failures = 2;
+        executor.settings.max_parallel = 1;
+
+        let tasks: Vec<Task> = ["one", "two", "three", "four"]
+            .iter()
+            .map(|name| Task {
+                name: name.to_string(),
+                run: "exit 1".into(),
+                ..Default::default()
+            })
+            .collect();
+        for parallel in [false, true] {
+            let hook = Hook {
+                parallel: Some(parallel),
+                tasks: tasks.clone(),
+                ..Default::default()
+            };
+            let result = executor.execute_hook(&hook).await.unwrap();
+            let names: Vec<&str> = result.tasks.iter().map(|t| t.name.as_str()).collect();
+            assert_eq!(names, vec!["one", "two"], "parallel = {}", parallel);
+        }
+
+        // The hook's own fail_fast takes precedence
+        let hook = Hook {
+            fail_fast: Some(false),
+            tasks,
+            ..Default::default()
+        };
+        assert_eq!(executor.execute_hook(&hook).await.unwrap().tasks.len(), 4);
+    }
+
+    #[test]
+    fn test_failure_limit_precedence() {
+        let mut executor = create_test_executor();
+        let hook = Hook::default();
+        assert_eq!(executor.failure_limit(&hook), 1);
+
+        executor.settings.fail_fast = false;
+        assert_eq!(executor.failure_limit(&hook), 0);
+
+        executor.settings.max_failures = 3;
+        assert_eq!(executor.failure_limit(&hook), 3);
+        let hook = Hook {
+            fail_fast: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(executor.failure_limit(&hook), 1);
+
+        // An explicit --max-failures wins over the hook's fail_fast
+        let executor = executor.with_max_failures(2);
+        assert_eq!(executor.failure_limit(&hook), 2);
+    }
+
+    #[test]
+    fn test_explain_task_reflects_branch_condition() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo = git2::Repository::init(temp_dir.path()).unwrap();
+        let signature = git2::Signature::now("Test", "test@test.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        repo.commit(
+            Some("refs/heads/develop"),
+            &signature,
+            &signature,
+            "initial",
+            &tree,
+            &[],
+        )
+        .unwrap();
+        repo.set_head("refs/heads/develop").unwrap();
+
+        let git_repo = GitRepository::open(temp_dir.path()).unwrap();
+        let executor = TaskExecutor::from_repo(Settings::default(), &git_repo);
+
+        let mut task = Task {
+            name: "deploy".to_string(),
+            run: "npm run deploy".into(),
+            condition: Some("branch == main".to_string()),
+            ..Default::default()
+        };
+
+        let explanation = executor.explain_task(&task);
+        assert!(!explanation.will_run);
+        assert!(explanation.reason.contains("branch == main"));
+
+        task.condition = Some("branch == develop".to_string());
+        let explanation = executor.explain_task(&task);
+        assert!(explanation.will_run);
+        assert!(explanation.reason.contains("condition true"));
+    }
+
+    #[test]
+    fn test_explain_task_glob_matches() {
+        let executor = create_test_executor();
+        let mut task = Task {
+            name: "lint".to_string(),
+            run: "cargo clippy".into(),
+            glob: Some("*.rs".to_string()),
+            ..Default::default()
+        };
+
+        let explanation = executor.explain_task(&task);
+        assert!(explanation.will_run);
+        assert_eq!(explanation.reason, "3 files match");
+
+        task.glob = Some("*.py".to_string());
+        let explanation = executor.explain_task(&task);
+        assert!(!explanation.will_run);
+        assert!(explanation.reason.contains("no staged files match"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_task_expands_cwd() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::env::set_var("FASTHOOKS_TEST_CWD", temp_dir.path());
+
+        let executor = create_test_executor();
+        let task = Task {
+            name: "pwd".to_string(),
+            run: "pwd".into(),
+            cwd: Some("${FASTHOOKS_TEST_CWD}".to_string()),
+            ..Default::default()
+        };
+
+        let result = executor.execute_task(&task, &[]).await.unwrap();
+        assert!(result.success);
+        let expected = temp_dir.path().canonicalize().unwrap();
+        let actual = PathBuf::from(result.stdout.trim()).canonicalize().unwrap();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_apply_branch_prefix() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let message_file = temp_dir.path().join("COMMIT_EDITMSG");
+        std::fs::write(&message_file, "Add login form\n").unwrap();
+
+        let mut executor = create_test_executor();
+        executor.current_branch = Some("feature/ABC-123-foo".to_string());
+        executor.hook_args = vec![message_file.to_string_lossy().to_string()];
+
+        executor.apply_branch_prefix(r"([A-Z]+-\d+)").unwrap();
+        let message = std::fs::read_to_string(&message_file).unwrap();
+        assert_eq!(message, "ABC-123 Add login form\n");
+
+        // Applying again (e.g. on amend) must not duplicate the prefix
+        executor.apply_branch_prefix(r"([A-Z]+-\d+)").unwrap();
+        let message = std::fs::read_to_string(&message_file).unwrap();
+        assert_eq!(message, "ABC-123 Add login form\n");
+    }
+
+    #[test]
+    fn test_apply_branch_prefix_no_match() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let message_file = temp_dir.path().join("COMMIT_EDITMSG");
+        std::fs::write(&message_file, "Fix typo\n").unwrap();
+
+        let mut executor = create_test_executor();
+        executor.hook_args = vec![message_file.to_string_lossy().to_string()];
+
+        executor.apply_branch_prefix(r"([A-Z]+-\d+)").unwrap();
+        let message = std::fs::read_to_string(&message_file).unwrap();
+        assert_eq!(message, "Fix typo\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_quiet_on_success_drops_output() {
+        let mut executor = create_test_executor();
+        executor.settings.quiet_on_success = true;
+        let mut task = Task {
+            name: "noisy".to_string(),
+            run: "echo progress; echo warning >&2".into(),
+            ..Default::default()
+        };
+
+        let result = executor.execute_task(&task, &[]).await.unwrap();
+        assert!(result.success);
+        assert!(result.stdout.is_empty());
+        assert!(result.stderr.is_empty());
+
+        // Failures keep their output
+        task.run = "echo progress; exit 1".into();
+        let result = executor.execute_task(&task, &[]).await.unwrap();
+        assert_eq!(result.stdout.trim(), "progress");
+
+        // The task setting overrides the global one
+        task.run = "echo progress".into();
+        task.quiet_on_success = Some(false);
+        let result = executor.execute_task(&task, &[]).await.unwrap();
+        assert_eq!(result.stdout.trim(), "progress");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_output_replayed_in_config_order() {
+        let mut executor = create_test_executor();
+        // `report` is declared first but has to wait for `build`
+        let hook = Hook {
+            parallel: Some(true),
+            tasks: vec![
+                Task {
+                    name: "report".to_string(),
+                    run: "echo report".into(),
+                    depends_on: vec!["build".to_string()],
+                    ..Default::default()
+                },
+                Task {
+                    name: "build".to_string(),
+                    run: "echo build".into(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let result = executor.execute_hook(&hook).await.unwrap();
+        let replayed: Vec<(&str, &str)> = result
+            .tasks
+            .iter()
+            .map(|t| (t.name.as_str(), t.stdout.trim()))
+            .collect();
+        assert_eq!(replayed, vec![("report", "report"), ("build", "build")]);
+
+        executor.settings.output_mode = OutputMode::ExecutionOrder;
+        let result = executor.execute_hook(&hook).await.unwrap();
+        let names: Vec<&str> = result.tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["build", "report"]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_dump_env_writes_task_environment() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let executor = create_test_executor().dump_env_to(temp_dir.path());
+        let task = Task {
+            name: "unit tests".to_string(),
+            run: "true".into(),
+            env: HashMap::from([
+                ("RUST_LOG".to_string(), "debug".to_string()),
+                ("QUOTED".to_string(), "it's {git.branch}".to_string()),
+            ]),
+            ..Default::default()
+        };
+
+        executor.execute_task(&task, &[]).await.unwrap();
+
+        let dumped = std::fs::read_to_string(temp_dir.path().join("unit_tests.env")).unwrap();
+        assert!(dumped.lines().any(|l| l == "RUST_LOG='debug'"));
+        assert!(dumped.lines().any(|l| l == "QUOTED='it'\\''s main'"));
+        // Inherited variables are included too
+        assert!(dumped.lines().any(|l| l.starts_with("PATH=")));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_array_stops_at_first_failure() {
+        let executor = create_test_executor();
+        let task: Task = toml::from_str(
+            r#"
+            name = "sequence"
+            run = ["echo one", "false", "echo two"]
+        "#,
+        )
+        .unwrap();
+
+        let result = executor.execute_task(&task, &[]).await.unwrap();
+        assert!(!result.success);
+        assert_eq!(result.stdout.trim(), "one");
+
+        // An `||` or a comment in one command doesn't reach the commands after it
+        let task: Task = toml::from_str(
+            r#"
+            name = "sequence"
+            run = ["false", "echo two || echo three"]
+        "#,
+        )
+        .unwrap();
+        let result = executor.execute_task(&task, &[]).await.unwrap();
+        assert!(!result.success);
+        assert_eq!(result.stdout.trim(), "");
+
+        let task: Task = toml::from_str(
+            r#"
+            name = "sequence"
+            run = ["echo one # first", "echo two"]
+        "#,
+        )
+        .unwrap();
+        let result = executor.execute_task(&task, &[]).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.stdout, "one\ntwo\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_build_command_appends_files_to_last_command() {
+        let executor = create_test_executor();
+        let task: Task = toml::from_str(
+            r#"
+            name = "lint"
+            run = ["npm ci", "eslint"]
+            glob = "*.js"
+        "#,
+        )
+        .unwrap();
+
+        let command = executor.build_command(&task, &[PathBuf::from("a.js")]);
+        assert_eq!(command, "{ npm ci\n} && { eslint a.js\n}");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_fallback_when_program_missing() {
+        let executor = create_test_executor();
+        let mut task = Task {
+            name: "lint".to_string(),
+            run: "fasthooks-missing-eslint --fix".into(),
+            fallback: Some("echo skipping".to_string()),
+            ..Default::default()
+        };
+
+        let result = executor.execute_task(&task, &[]).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.command, "echo skipping");
+        assert_eq!(result.stdout.trim(), "skipping");
+
+        // An installed program runs as usual
+        task.run = "echo linting".into();
+        let result = executor.execute_task(&task, &[]).await.unwrap();
+        assert_eq!(result.stdout.trim(), "linting");
+
+        // An empty fallback skips the task
+        task.run = "fasthooks-missing-eslint --fix".into();
+        task.fallback = Some(String::new());
+        let hook = Hook {
+            tasks: vec![task],
+            ..Default::default()
+        };
+        let result = executor.execute_hook(&hook).await.unwrap();
+        assert!(result.success);
+        assert!(result.tasks.is_empty());
+        assert_eq!(
+            result.skipped[0].reason,
+            "fasthooks-missing-eslint is not installed and the fallback is empty"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_task_pipes_stdin() {
+        let executor = create_test_executor();
+        let task = Task {
+            name: "cat".to_string(),
+            run: "cat".into(),
+            stdin: Some("hello from stdin\n".to_string()),
+            ..Default::default()
+        };
+
+        let result = executor.execute_task(&task, &[]).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.stdout, "hello from stdin\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_task_without_stdin_sees_eof() {
+        let executor = create_test_executor();
+        let task = Task {
+            name: "cat".to_string(),
+            run: "cat".into(),
+            ..Default::default()
+        };
+
+        let result = executor.execute_task(&task, &[]).await.unwrap();
+        assert!(result.success);
+        assert!(result.stdout.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_preflight_reports_missing_command() {
+        let mut executor = create_test_executor();
+        executor.settings.preflight = true;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let marker = temp_dir.path().join("ran");
+        let hook = Hook {
+            tasks: vec![
+                Task {
+                    name: "marker".to_string(),
+                    run: format!("echo ran > {}", marker.display()).into(),
+                    ..Default::default()
+                },
+                Task {
+                    name: "lint".to_string(),
+                    run: "fasthooks-missing-eslint --fix".into(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let error = executor.execute_hook(&hook).await.unwrap_err().to_string();
+        assert!(error.contains("command not found: fasthooks-missing-eslint"));
+        assert!(error.contains("task 'lint'"));
+        assert!(!marker.exists());
+    }
+
+    #[test]
+    fn test_evaluate_condition_exists_glob() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let nested = temp_dir.path().join("src").join("nested");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(nested.join("lib.rs"), "").unwrap();
+
+        let executor = create_test_executor();
+        let root = temp_dir.path().display();
+        let mut task = Task {
+            name: "clippy".to_string(),
+            run: "cargo clippy".into(),
+            condition: Some(format!("exists:{}/src/**/*.rs", root)),
+            ..Default::default()
+        };
+        assert!(executor.evaluate_condition(&task));
+
+        task.condition = Some(format!("exists:{}/src/**/*.py", root));
+        assert!(!executor.evaluate_condition(&task));
+
+        task.condition = Some(format!("!exists:{}/src/**/*.py", root));
+        assert!(executor.evaluate_condition(&task));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_task_success_codes() {
+        let executor = create_test_executor();
+        let mut task = Task {
+            name: "grep".to_string(),
+            run: "exit 1".into(),
+            success_codes: vec![0, 1],
+            ..Default::default()
+        };
+
+        let result = executor.execute_task(&task, &[]).await.unwrap();
+        assert!(result.success);
+
+        // Codes outside the list still fail the task
+        task.run = "exit 2".into();
+        let result = executor.execute_task(&task, &[]).await.unwrap();
+        assert!(!result.success);
+        assert_eq!(result.exit_code, 2);
+
+        task.success_codes = vec![0];
+        task.run = "exit 1".into();
+        let result = executor.execute_task(&task, &[]).await.unwrap();
+        assert!(!result.success);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_hook_reports_glob_skips() {
+        let executor = create_test_executor();
+        let hook = Hook {
+            tasks: vec![
+                Task {
+                    name: "lint".to_string(),
+                    run: "true".into(),
+                    glob: Some("*.js".to_string()),
+                    ..Default::default()
+                },
+                Task {
+                    name: "fmt".to_string(),
+                    run: "true".into(),
+                    glob: Some("*.rs".to_string()),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let result = executor.execute_hook(&hook).await.unwrap();
+
+        assert_eq!(result.tasks.len(), 1);
+        assert_eq!(result.skipped.len(), 1);
+        assert_eq!(result.skipped[0].name, "lint");
+        assert_eq!(
+            result.skipped[0].reason,
+            "no files matched *.js among 6 staged"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_skipped_tasks_report_condition_and_glob_reasons() {
+        let mut executor = create_test_executor();
+        executor.current_branch = Some("feature/login".to_string());
+        let hook = Hook {
+            tasks: vec![
+                Task {
+                    name: "release-check".to_string(),
+                    run: "true".into(),
+                    condition: Some("branch == main".to_string()),
+                    ..Default::default()
+                },
+                Task {
+                    name: "stylelint".to_string(),
+                    run: "true".into(),
+                    glob: Some("*.css".to_string()),
+                    ..Default::default()
+                },
+                Task {
+                    name: "test".to_string(),
+                    run: "true".into(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let result = executor.execute_hook(&hook).await.unwrap();
+
+        assert_eq!(result.tasks.len(), 1);
+        let skipped: Vec<(&str, &str)> = result
+            .skipped
+            .iter()
+            .map(|s| (s.name.as_str(), s.reason.as_str()))
+            .collect();
+        assert_eq!(
+            skipped,
+            vec![
+                ("release-check", "condition 'branch == main' is false"),
+                ("stylelint", "no files matched *.css among 6 staged"),
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_task_records_command() {
+        let executor = create_test_executor();
+        let task = Task {
+            name: "lint".to_string(),
+            run: "echo {files} $1".into(),
+            glob: Some("*.rs".to_string()),
+            ..Default::default()
+        };
+        let files = executor.filter_files(&task);
+
+        let result = executor.execute_task(&task, &files).await.unwrap();
+        assert_eq!(
+            result.command,
+            "echo src/main.rs src/lib.rs tests/test.rs arg1"
+        );
+        assert_eq!(result.file_count, Some(3));
+    }
+
+    #[test]
+    fn test_execution_mode_routing() {
+        assert_eq!(ExecutionMode::select(true, false), ExecutionMode::Parallel);
+        assert_eq!(
+            ExecutionMode::select(true, true),
+            ExecutionMode::Dependencies
+        );
+        assert_eq!(
+            ExecutionMode::select(false, false),
+            ExecutionMode::Sequential
+        );
+        assert_eq!(
+            ExecutionMode::select(false, true),
+            ExecutionMode::Sequential
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_hook_all_routing_combinations() {
+        for (parallel, with_deps) in [(true, false), (true, true), (false, false), (false, true)] {
+            let temp_dir = tempfile::TempDir::new().unwrap();
+            let log = temp_dir.path().join("log");
+            let depends_on = if with_deps {
+                vec!["build".to_string()]
+            } else {
+                Vec::new()
+            };
+
+            let hook = Hook {
+                tasks: vec![
+                    Task {
+                        name: "test".to_string(),
+                        // With dependencies, build must have finished first
+                        run: if with_deps {
+                            format!("grep -q build {0} && echo test >> {0}", log.display())
+                        } else {
+                            format!("echo test >> {}", log.display())
+                        }
+                        .into(),
+                        depends_on,
+                        ..Default::default()
+                    },
+                    Task {
+                        name: "build".to_string(),
+                        run: format!("sleep 0.1 && echo build >> {}", log.display()).into(),
+                        ..Default::default()
+                    },
+                ],
+                parallel: Some(parallel),
+                ..Default::default()
+            };
+
+            let result = create_test_executor().execute_hook(&hook).await.unwrap();
+            assert!(
+                result.success,
+                "parallel={} deps={}: {:?}",
+                parallel, with_deps, result.tasks
+            );
+            assert_eq!(result.tasks.len(), 2);
+
+            let lines = std::fs::read_to_string(&log).unwrap();
+            if with_deps {
+                assert_eq!(lines, "build\ntest\n");
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_build_command_uses_unix_variant() {
+        let executor = create_test_executor();
+        let mut task = Task {
+            name: "setup".to_string(),
+            run: "echo default".into(),
+            run_windows: Some("scripts\\setup.cmd".into()),
+            ..Default::default()
+        };
+        assert_eq!(executor.build_command(&task, &[]), "echo default");
+
+        task.run_unix = Some("./scripts/setup.sh".into());
+        assert_eq!(executor.build_command(&task, &[]), "./scripts/setup.sh");
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_build_command_uses_windows_variant() {
+        let executor = create_test_executor();
+        let mut task = Task {
+            name: "setup".to_string(),
+            run: "echo default".into(),
+            run_unix: Some("./scripts/setup.sh".into()),
+            ..Default::default()
+        };
+        assert_eq!(executor.build_command(&task, &[]), "echo default");
+
+        task.run_windows = Some("scripts\\setup.cmd".into());
+        assert_eq!(executor.build_command(&task, &[]), "scripts\\setup.cmd");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_task_expands_git_env() {
+        let executor = create_test_executor();
+        let task = Task {
+            name: "env".to_string(),
+            run: "echo \"$BRANCH $SHA\"".into(),
+            env: HashMap::from([
+                ("BRANCH".to_string(), "{git.branch}".to_string()),
+                ("SHA".to_string(), "sha-{git.sha}".to_string()),
+            ]),
+            ..Default::default()
+        };
+
+        let result = executor.execute_task(&task, &[]).await.unwrap();
+        assert_eq!(
+            result.stdout.trim(),
+            "main sha-0123456789abcdef0123456789abcdef01234567"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_task_fail_on_stderr() {
+        let executor = create_test_executor();
+        let mut task = Task {
+            name: "warn".to_string(),
+            run: "echo 'warning: deprecated' >&2".into(),
+            ..Default::default()
+        };
+
+        let result = executor.execute_task(&task, &[]).await.unwrap();
+        assert!(result.success);
+
+        task.fail_on_stderr = true;
+        let result = executor.execute_task(&task, &[]).await.unwrap();
+        assert!(!result.success);
+        assert!(result.stderr.contains("warning: deprecated"));
+        assert!(result.stderr.contains("fail_on_stderr"));
+
+        // Whitespace-only stderr doesn't count
+        task.run = "echo >&2".into();
+        let result = executor.execute_task(&task, &[]).await.unwrap();
+        assert!(result.success);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_task_writes_output_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let executor = create_test_executor();
+        let task = Task {
+            name: "coverage".to_string(),
+            run: "echo 'coverage: 87%'".into(),
+            cwd: Some(temp_dir.path().display().to_string()),
+            output_file: Some("reports/{git.branch}.txt".to_string()),
+            ..Default::default()
+        };
+
+        let result = executor.execute_task(&task, &[]).await.unwrap();
+        assert!(result.success);
+        // Output is still captured for the report
+        assert_eq!(result.stdout, "coverage: 87%\n");
+
+        let written = std::fs::read_to_string(temp_dir.path().join("reports/main.txt")).unwrap();
+        assert_eq!(written, "coverage: 87%\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_task_sets_files_env() {
+        let executor = create_test_executor();
+        let task = Task {
+            name: "eslint".to_string(),
+            run: "printf '%s' \"$ESLINT_FILES\"".into(),
+            glob: Some("*.ts".to_string()),
+            files_env: Some("ESLINT_FILES".to_string()),
+            ..Default::default()
+        };
+
+        let files = executor.filter_files(&task);
+        assert_eq!(
+            executor.build_command(&task, &files),
+            task.run.script(),
+            "files aren't appended when passed via files_env"
+        );
+
+        let result = executor.execute_task(&task, &files).await.unwrap();
+        assert!(result.success);
+        let listed: Vec<&str> = result.stdout.lines().collect();
+        let expected: Vec<String> = files.iter().map(|f| f.display().to_string()).collect();
+        assert_eq!(listed, expected);
+        assert!(!listed.is_empty());
+    }
+
+    #[test]
+    fn test_split_interactive_keeps_order() {
+        let task = |name: &str, interactive: bool| Task {
+            name: name.to_string(),
+            run: "true".into(),
+            interactive,
+            ..Default::default()
+        };
+        let tasks = [
+            task("lint", false),
+            task("confirm", true),
+            task("test", false),
+            task("review", true),
+        ];
+
+        let (prelude, batch) = TaskExecutor::split_interactive(tasks.iter().collect());
+        let names = |tasks: &[&Task]| tasks.iter().map(|t| t.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&prelude), vec!["confirm", "review"]);
+        assert_eq!(names(&batch), vec!["lint", "test"]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_interactive_task_runs_first_with_inherited_stdio() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let marker = temp_dir.path().join("confirmed");
+        let executor = TaskExecutor::with_files(Settings::default(), Vec::new()).unwrap();
+        let hook = Hook {
+            tasks: vec![
+                Task {
+                    name: "check".to_string(),
+                    run: format!("test -f {}", marker.display()).into(),
+                    ..Default::default()
+                },
+                Task {
+                    name: "confirm".to_string(),
+                    run: format!("touch {} && echo confirmed", marker.display()).into(),
+                    interactive: true,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let result = executor.execute_hook(&hook).await.unwrap();
+
+        let names: Vec<&str> = result.tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["confirm", "check"]);
+        assert!(result.success);
+        // Output went to the terminal instead of being captured
+        assert!(result.tasks[0].stdout.is_empty());
+    }
+
+    #[test]
+    fn test_filter_files_case_insensitive_globs() {
+        let mut executor = create_test_executor();
+        executor.staged_files = vec![PathBuf::from("src/MAIN.RS"), PathBuf::from("lib.rs")];
+        let task = Task {
+            name: "fmt".to_string(),
+            run: "rustfmt".into(),
+            glob: Some("*.rs".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(executor.filter_files(&task), vec![PathBuf::from("lib.rs")]);
+
+        executor.settings.case_insensitive_globs = true;
+        assert_eq!(
+            executor.filter_files(&task),
+            vec![PathBuf::from("src/MAIN.RS"), PathBuf::from("lib.rs")]
+        );
+    }
+
+    #[test]
+    fn test_build_command_with_dirs() {
+        let mut executor = create_test_executor();
+        executor.staged_files = vec![
+            PathBuf::from("apps/web/src/main.ts"),
+            PathBuf::from("libs/ui/button.ts"),
+            PathBuf::from("apps/api/server.ts"),
+            PathBuf::from("README.md"),
+        ];
+        let task = Task {
+            name: "affected".to_string(),
+            run: "nx affected --projects {dirs}".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            executor.build_command(&task, &[]),
+            "nx affected --projects . apps libs"
+        );
+
+        // Globbed tasks only consider their matched files
+        let task = Task {
+            glob: Some("libs/**/*.ts".to_string()),
+            ..task
+        };
+        let files = executor.filter_files(&task);
+        assert_eq!(
+            executor.build_command(&task, &files),
+            "nx affected --projects libs"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dirs_include_all_staged_files_in_spawned_tasks() {
+        let mut executor = create_test_executor();
+        executor.staged_files = vec![
+            PathBuf::from("apps/web/main.ts"),
+            PathBuf::from("libs/ui/button.ts"),
+        ];
+        let affected = Task {
+            name: "affected".to_string(),
+            run: "echo projects: {dirs}".into(),
+            ..Default::default()
+        };
+        let other = Task {
+            name: "other".to_string(),
+            run: "echo other".into(),
+            ..Default::default()
+        };
+
+        // Parallel mode
+        let hook = Hook {
+            parallel: Some(true),
+            tasks: vec![affected.clone(), other.clone()],
+            ..Default::default()
+        };
+        let result = executor.execute_hook(&hook).await.unwrap();
+        let task = result.tasks.iter().find(|t| t.name == "affected").unwrap();
+        assert_eq!(task.stdout.trim(), "projects: apps libs");
+
+        // Dependency mode
+        let hook = Hook {
+            parallel: Some(true),
+            tasks: vec![
+                other,
+                Task {
+                    depends_on: vec!["other".to_string()],
+                    ..affected
+                },
+            ],
+            ..Default::default()
+        };
+        let result = executor.execute_hook(&hook).await.unwrap();
+        let task = result.tasks.iter().find(|t| t.name == "affected").unwrap();
+        assert_eq!(task.stdout.trim(), "projects: apps libs");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_without_git_runs_outside_repository() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let file = temp_dir.path().join("main.rs");
+        std::fs::write(&file, "fn main() {}\n").unwrap();
+
+        let executor = TaskExecutor::without_git(Settings::default(), vec![file.clone()]);
+        assert!(executor.current_branch.is_none());
+        let task = Task {
+            name: "check".to_string(),
+            run: "test -f".into(),
+            glob: Some("*.rs".to_string()),
+            cwd: Some(temp_dir.path().display().to_string()),
+            ..Default::default()
+        };
+
+        let hook = Hook {
+            tasks: vec![task],
+            ..Default::default()
+        };
+        let result = executor.execute_hook(&hook).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.tasks[0].file_count, Some(1));
+    }
 }
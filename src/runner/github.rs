@@ -0,0 +1,307 @@
+//! GitHub Actions `::error` workflow commands for failed tasks, so failures show in the PR UI
+
+use super::TaskResult;
+use regex::Regex;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Whether the run is inside GitHub Actions, which enables annotations by default
+pub fn in_github_actions() -> bool {
+    std::env::var("GITHUB_ACTIONS").is_ok_and(|value| value == "true")
+}
+
+/// A problem reported at a location in a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    pub file: String,
+    pub line: u32,
+    pub col: Option<u32>,
+    pub warning: bool,
+    pub message: String,
+}
+
+impl Annotation {
+    /// Render as a workflow command, e.g. `::error file=a.js,line=1,col=5,title=lint::msg`
+    pub fn to_command(&self, task_name: &str) -> String {
+        let mut properties = format!("file={},line={}", escape_property(&self.file), self.line);
+        if let Some(col) = self.col {
+            properties.push_str(&format!(",col={}", col));
+        }
+        properties.push_str(&format!(",title={}", escape_property(task_name)));
+
+        let level = if self.warning { "warning" } else { "error" };
+        format!("::{} {}::{}", level, properties, escape_data(&self.message))
+    }
+}
+
+/// Workflow commands for a failed task: one per problem found in its output, or a
+/// single unlocated error when the output has no recognizable locations. Paths in the
+/// output are resolved against the task's directory `dir` and made relative to `root`.
+pub fn annotations(task: &TaskResult, dir: &Path, root: &Path) -> Vec<String> {
+    if task.success {
+        return Vec::new();
+    }
+
+    let output = format!("{}\n{}", task.stdout, task.stderr);
+    let found = parse_locations(&output, dir, root);
+    if found.is_empty() {
+        return vec![format!(
+            "::error title={}::{}",
+            escape_property(&task.name),
+            escape_data(&format!(
+                "Task '{}' failed (exit code {})",
+                task.name, task.exit_code
+            ))
+        )];
+    }
+
+    found.iter().map(|a| a.to_command(&task.name)).collect()
+}
+
+/// Patterns for the output formats, compiled once
+struct Patterns {
+    unix: Regex,
+    stylish: Regex,
+    arrow: Regex,
+    diagnostic: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| Patterns {
+        unix: Regex::new(r"^([^\s:]+):(\d+)(?::(\d+))?:\s*(.+)$").unwrap(),
+        stylish: Regex::new(r"^\s+(\d+):(\d+)\s+(error|warning)\s+(.+?)\s*$").unwrap(),
+        arrow: Regex::new(r"^\s*--> ([^:]+):(\d+):(\d+)").unwrap(),
+        diagnostic: Regex::new(r"^(error|warning)(?:\[\w+\])?: (.+)$").unwrap(),
+    })
+}
+
+/// Find `file:line[:col]: message` lines, ESLint's default "stylish" blocks and rustc's
+/// `--> file:line:col` locations in linter output
+pub fn parse_locations(output: &str, dir: &Path, root: &Path) -> Vec<Annotation> {
+    let Patterns {
+        unix,
+        stylish,
+        arrow,
+        diagnostic,
+    } = patterns();
+
+    let mut annotations = Vec::new();
+    let mut stylish_file: Option<String> = None;
+    let mut last_diagnostic: Option<(bool, String)> = None;
+
+    for raw_line in output.lines() {
+        let line = strip_ansi(raw_line);
+
+        if let Some(caps) = stylish.captures(&line) {
+            if let Some(file) = &stylish_file {
+                annotations.push(Annotation {
+                    file: file.clone(),
+                    line: caps[1].parse().unwrap_or(1),
+                    col: caps[2].parse().ok(),
+                    warning: &caps[3] == "warning",
+                    message: caps[4].to_string(),
+                });
+                continue;
+            }
+        }
+        if let Some(caps) = arrow.captures(&line) {
+            if let Some((warning, message)) = last_diagnostic.take() {
+                annotations.push(Annotation {
+                    file: relative(&caps[1], dir, root),
+                    line: caps[2].parse().unwrap_or(1),
+                    col: caps[3].parse().ok(),
+                    warning,
+                    message,
+                });
+            }
+            continue;
+        }
+        if let Some(caps) = diagnostic.captures(&line) {
+            last_diagnostic = Some((&caps[1] == "warning", caps[2].to_string()));
+            continue;
+        }
+        if let Some(caps) = unix
+            .captures(&line)
+            .filter(|caps| looks_like_path(&caps[1]))
+        {
+            let message = caps[4].to_string();
+            annotations.push(Annotation {
+                file: relative(&caps[1], dir, root),
+                line: caps[2].parse().unwrap_or(1),
+                col: caps.get(3).and_then(|m| m.as_str().parse().ok()),
+                warning: message.to_lowercase().starts_with("warning"),
+                message,
+            });
+            continue;
+        }
+
+        // A bare path starts an ESLint stylish block; anything else ends it
+        let trimmed = line.trim();
+        stylish_file = (!trimmed.is_empty() && !trimmed.contains(' ') && !line.starts_with(' '))
+            .then(|| relative(trimmed, dir, root));
+    }
+
+    annotations
+}
+
+/// Whether a `file:line` prefix names a file rather than e.g. the hours of a
+/// `12:30:45` timestamp
+fn looks_like_path(file: &str) -> bool {
+    file.chars().any(char::is_alphabetic) && file.contains(['.', '/', '\\'])
+}
+
+/// Resolve `file` against the task's directory and make it relative to `root`, as
+/// annotations expect; paths outside `root` are kept as written
+fn relative(file: &str, dir: &Path, root: &Path) -> String {
+    let mut path = PathBuf::new();
+    for component in dir.join(file).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                path.pop();
+            }
+            other => path.push(other),
+        }
+    }
+    path.strip_prefix(root)
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_else(|_| file.to_string())
+}
+
+/// Remove ANSI color codes that linters add even when piped
+fn strip_ansi(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Escape a workflow command message
+fn escape_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a workflow command property value
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failed(stdout: &str) -> TaskResult {
+        TaskResult::failure("lint".to_string(), 1, stdout.to_string(), String::new(), 10)
+    }
+
+    #[test]
+    fn test_eslint_stylish_output() {
+        let output = "\n/repo/src/app.js\n  1:10  error    'x' is defined but never used  no-unused-vars\n  4:1   warning  Unexpected console statement   no-console\n\n\u{1b}[31m✖ 2 problems (1 error, 1 warning)\u{1b}[39m\n";
+        assert_eq!(
+            annotations(&failed(output), Path::new("/repo"), Path::new("/repo")),
+            vec![
+                "::error file=src/app.js,line=1,col=10,title=lint::'x' is defined but never used  no-unused-vars",
+                "::warning file=src/app.js,line=4,col=1,title=lint::Unexpected console statement   no-console",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_eslint_unix_output() {
+        let output = "src/app.js:1:10: 'x' is defined but never used [Error/no-unused-vars]\nsrc/util.js:7:3: Missing semicolon. [Error/semi]\n\n2 problems";
+        assert_eq!(
+            annotations(&failed(output), Path::new("/repo"), Path::new("/repo")),
+            vec![
+                "::error file=src/app.js,line=1,col=10,title=lint::'x' is defined but never used [Error/no-unused-vars]",
+                "::error file=src/util.js,line=7,col=3,title=lint::Missing semicolon. [Error/semi]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rustc_arrow_output() {
+        let output = "error[E0425]: cannot find value `y` in this scope\n --> src/main.rs:3:13\n  |\n3 |     let x = y;\n";
+        assert_eq!(
+            parse_locations(output, Path::new("/repo"), Path::new("/repo")),
+            vec![Annotation {
+                file: "src/main.rs".to_string(),
+                line: 3,
+                col: Some(13),
+                warning: false,
+                message: "cannot find value `y` in this scope".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_unlocated_failure_and_success() {
+        let task = TaskResult::failure(
+            "test".to_string(),
+            101,
+            String::new(),
+            "boom".to_string(),
+            10,
+        );
+        assert_eq!(
+            annotations(&task, Path::new("/repo"), Path::new("/repo")),
+            vec!["::error title=test::Task 'test' failed (exit code 101)"]
+        );
+
+        let ok = TaskResult::success(
+            "lint".to_string(),
+            "a.js:1:1: fine".to_string(),
+            String::new(),
+            5,
+        );
+        assert!(annotations(&ok, Path::new("/repo"), Path::new("/repo")).is_empty());
+    }
+
+    #[test]
+    fn test_paths_resolved_against_task_cwd() {
+        let output = "src/app.js:3:1: Missing semicolon. [Error/semi]\n/repo/frontend/src/b.js:1:1: Unexpected var [Error/no-var]\n../shared/c.js:2:4: Bad [Error/x]";
+        let files: Vec<String> =
+            parse_locations(output, Path::new("/repo/frontend"), Path::new("/repo"))
+                .into_iter()
+                .map(|a| a.file)
+                .collect();
+        assert_eq!(
+            files,
+            vec!["frontend/src/app.js", "frontend/src/b.js", "shared/c.js"]
+        );
+    }
+
+    #[test]
+    fn test_timestamps_are_not_locations() {
+        let output = "12:30:45: starting lint\n[12:30:46] done";
+        assert!(parse_locations(output, Path::new("/repo"), Path::new("/repo")).is_empty());
+    }
+
+    #[test]
+    fn test_escaping() {
+        let annotation = Annotation {
+            file: "a,b.js".to_string(),
+            line: 2,
+            col: None,
+            warning: false,
+            message: "100% wrong\nsecond line".to_string(),
+        };
+        assert_eq!(
+            annotation.to_command("lint: js"),
+            "::error file=a%2Cb.js,line=2,title=lint%3A js::100%25 wrong%0Asecond line"
+        );
+    }
+}
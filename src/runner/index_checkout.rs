@@ -0,0 +1,86 @@
+//! Scratch checkout of the index for isolated (`--isolated`) runs
+
+use crate::hooks::GitRepository;
+use anyhow::{Context, Result};
+use std::path::Path;
+use tempfile::TempDir;
+
+/// A temporary directory holding exactly the staged files, removed when dropped
+pub struct IndexCheckout {
+    dir: TempDir,
+}
+
+impl IndexCheckout {
+    /// Check out the repository's index into a new, uniquely named directory under the
+    /// system temp dir
+    pub fn create(repo: &GitRepository) -> Result<Self> {
+        // The directory is removed on drop, also when the checkout below fails
+        let dir = tempfile::Builder::new()
+            .prefix("fasthooks-index-")
+            .tempdir()
+            .context("Failed to create a temporary directory for the index checkout")?;
+        repo.checkout_index_to(dir.path())?;
+        Ok(Self { dir })
+    }
+
+    /// Directory the staged files were written to
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Hook, Settings, Task};
+    use crate::runner::TaskExecutor;
+    use git2::Repository;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_tasks_see_only_staged_content() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let raw = Repository::init(root).unwrap();
+
+        // Stage one version, then change the worktree and add an untracked file
+        std::fs::write(root.join("file.txt"), "staged\n").unwrap();
+        let mut index = raw.index().unwrap();
+        index.add_path(Path::new("file.txt")).unwrap();
+        index.write().unwrap();
+        std::fs::write(root.join("file.txt"), "unstaged\n").unwrap();
+        std::fs::write(root.join("untracked.txt"), "noise\n").unwrap();
+
+        let repo = GitRepository::open(root).unwrap();
+        let checkout = IndexCheckout::create(&repo).unwrap();
+        let dir = checkout.path().to_path_buf();
+
+        let hook = Hook {
+            tasks: vec![Task {
+                name: "inspect".to_string(),
+                run: "cat file.txt; ls".into(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let executor = TaskExecutor::from_repo(Settings::default(), &repo).in_dir(&dir);
+        let result = executor.execute_hook(&hook).await.unwrap();
+
+        let stdout = &result.tasks[0].stdout;
+        assert_eq!(stdout.lines().next(), Some("staged"));
+        assert!(!stdout.contains("untracked.txt"));
+        // The worktree is untouched
+        assert_eq!(
+            std::fs::read_to_string(root.join("file.txt")).unwrap(),
+            "unstaged\n"
+        );
+
+        // Checkouts don't share a directory
+        let other = IndexCheckout::create(&repo).unwrap();
+        assert_ne!(other.path(), dir);
+
+        drop(checkout);
+        assert!(!dir.exists());
+        assert!(other.path().exists());
+    }
+}
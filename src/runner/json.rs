@@ -0,0 +1,66 @@
+//! JSON serialization of hook results, for `--output` files and the webhook
+
+use super::HookResult;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::Path;
+
+/// The hook's name alongside its full result
+#[derive(Serialize)]
+struct Report<'a> {
+    hook: &'a str,
+    #[serde(flatten)]
+    result: &'a HookResult,
+}
+
+/// Serialize one hook run as a JSON object
+pub fn to_json(hook_name: &str, result: &HookResult) -> Result<String> {
+    serde_json::to_string_pretty(&Report {
+        hook: hook_name,
+        result,
+    })
+    .context("Failed to serialize hook result")
+}
+
+/// Write the JSON result of a hook run to `path`
+pub fn write_report(path: &Path, hook_name: &str, result: &HookResult) -> Result<()> {
+    std::fs::write(path, to_json(hook_name, result)?)
+        .with_context(|| format!("Failed to write results to {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::{SkippedTask, TaskResult};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_report_contains_tasks() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("result.json");
+        let result = HookResult::new(
+            vec![
+                TaskResult::success("fmt".to_string(), String::new(), String::new(), 12),
+                TaskResult::failure("lint".to_string(), 1, String::new(), "err".to_string(), 30),
+            ],
+            35,
+        )
+        .with_skipped(vec![SkippedTask {
+            name: "css".to_string(),
+            reason: "no files matched *.css among 2 staged".to_string(),
+        }]);
+
+        write_report(&path, "pre-commit", &result).unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(json["hook"], "pre-commit");
+        assert_eq!(json["success"], false);
+        let tasks = json["tasks"].as_array().unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[1]["name"], "lint");
+        assert_eq!(tasks[1]["stderr"], "err");
+        assert_eq!(json["skipped"][0]["name"], "css");
+        assert_eq!(json["stats"]["wall_time_ms"], 35);
+    }
+}
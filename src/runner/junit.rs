@@ -0,0 +1,114 @@
+//! JUnit XML reports for CI dashboards
+
+use super::HookResult;
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// Render a hook result as a JUnit XML document with one `<testcase>` per task
+pub fn to_junit(hook_name: &str, result: &HookResult) -> String {
+    let failures = result.tasks.iter().filter(|t| !t.success).count();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+
+    xml.push_str(&format!(
+        "<testsuites name=\"fasthooks\" tests=\"{}\" failures=\"{}\" time=\"{}\">\n",
+        result.tasks.len(),
+        failures,
+        seconds(result.stats.wall_time_ms)
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" time=\"{}\">\n",
+        escape(hook_name),
+        result.tasks.len(),
+        failures,
+        seconds(result.stats.wall_time_ms)
+    ));
+
+    for task in &result.tasks {
+        let attrs = format!(
+            "name=\"{}\" classname=\"{}\" time=\"{}\"",
+            escape(&task.name),
+            escape(hook_name),
+            seconds(task.duration_ms)
+        );
+
+        if task.success {
+            xml.push_str(&format!("    <testcase {} />\n", attrs));
+        } else {
+            xml.push_str(&format!("    <testcase {}>\n", attrs));
+            xml.push_str(&format!(
+                "      <failure message=\"exit code {}\">{}</failure>\n",
+                task.exit_code,
+                escape(&task.stderr)
+            ));
+            xml.push_str("    </testcase>\n");
+        }
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+/// Write the JUnit report for a hook result to `path`
+pub fn write_report(path: &Path, hook_name: &str, result: &HookResult) -> Result<()> {
+    std::fs::write(path, to_junit(hook_name, result))
+        .with_context(|| format!("Failed to write JUnit report to {}", path.display()))
+}
+
+/// Format milliseconds as JUnit's fractional seconds
+fn seconds(ms: u64) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
+
+/// Escape text for use in XML attributes and content
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than tab/newline are invalid in XML 1.0
+            c if c.is_control() && c != '\n' && c != '\t' && c != '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::TaskResult;
+
+    #[test]
+    fn test_junit_report() {
+        let result = HookResult::new(
+            vec![
+                TaskResult::success("fmt".to_string(), String::new(), String::new(), 120),
+                TaskResult::failure(
+                    "test".to_string(),
+                    101,
+                    String::new(),
+                    "assertion failed: a < b".to_string(),
+                    2500,
+                ),
+            ],
+            2600,
+        );
+
+        let xml = to_junit("pre-push", &result);
+
+        assert_eq!(xml.matches("<testcase ").count(), 2);
+        assert_eq!(xml.matches("<failure ").count(), 1);
+        assert!(
+            xml.contains("<testsuite name=\"pre-push\" tests=\"2\" failures=\"1\" time=\"2.600\">")
+        );
+        assert!(xml.contains("<testcase name=\"test\" classname=\"pre-push\" time=\"2.500\">"));
+        assert!(
+            xml.contains("<failure message=\"exit code 101\">assertion failed: a &lt; b</failure>")
+        );
+        assert_eq!(xml.matches("</testcase>").count(), 1);
+    }
+}
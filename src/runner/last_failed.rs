@@ -0,0 +1,109 @@
+//! Failed task tracking for `run --select-failed`
+
+use super::TaskResult;
+use crate::utils::ensure_dir;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// File inside the state directory recording the failed tasks of each hook
+const LAST_FAILED_FILE: &str = "last-failed.json";
+
+/// Reads and writes the tasks that failed in the last run of each hook
+pub struct LastFailed;
+
+impl LastFailed {
+    /// Load the names of the tasks that failed in the hook's last run
+    pub fn load(state_dir: &Path, hook_name: &str) -> Vec<String> {
+        Self::read_all(state_dir)
+            .remove(hook_name)
+            .unwrap_or_default()
+    }
+
+    /// Merge this run's results into the hook's failures: tasks that passed are dropped,
+    /// tasks that failed are added, and tasks that didn't run (e.g. not selected with
+    /// `--only`) keep their previous state
+    pub fn record(state_dir: &Path, hook_name: &str, tasks: &[TaskResult]) -> Result<()> {
+        let mut all = Self::read_all(state_dir);
+        let mut failed = all.remove(hook_name).unwrap_or_default();
+        failed.retain(|name| !tasks.iter().any(|t| t.success && &t.name == name));
+        for task in tasks.iter().filter(|t| !t.success) {
+            if !failed.contains(&task.name) {
+                failed.push(task.name.clone());
+            }
+        }
+        if !failed.is_empty() {
+            all.insert(hook_name.to_string(), failed);
+        }
+
+        ensure_dir(state_dir)?;
+        let path = state_dir.join(LAST_FAILED_FILE);
+        fs::write(&path, serde_json::to_string_pretty(&all)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Read all recorded failures, treating a missing or corrupt file as empty
+    fn read_all(state_dir: &Path) -> HashMap<String, Vec<String>> {
+        fs::read_to_string(state_dir.join(LAST_FAILED_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn result(name: &str, success: bool) -> TaskResult {
+        if success {
+            TaskResult::success(name.to_string(), String::new(), String::new(), 1)
+        } else {
+            TaskResult::failure(name.to_string(), 1, String::new(), String::new(), 1)
+        }
+    }
+
+    #[test]
+    fn test_record_and_clear_failures() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_dir = temp_dir.path().join("fasthooks");
+        assert!(LastFailed::load(&state_dir, "pre-commit").is_empty());
+
+        LastFailed::record(
+            &state_dir,
+            "pre-commit",
+            &[result("fmt", true), result("lint", false)],
+        )
+        .unwrap();
+        LastFailed::record(&state_dir, "pre-push", &[result("test", false)]).unwrap();
+        assert_eq!(LastFailed::load(&state_dir, "pre-commit"), vec!["lint"]);
+
+        LastFailed::record(&state_dir, "pre-commit", &[result("lint", true)]).unwrap();
+        assert!(LastFailed::load(&state_dir, "pre-commit").is_empty());
+        assert_eq!(LastFailed::load(&state_dir, "pre-push"), vec!["test"]);
+    }
+
+    #[test]
+    fn test_record_keeps_failures_of_tasks_that_did_not_run() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_dir = temp_dir.path().join("fasthooks");
+        LastFailed::record(
+            &state_dir,
+            "pre-commit",
+            &[result("lint", false), result("test", false)],
+        )
+        .unwrap();
+
+        // `--only lint` fixes lint; test didn't run and is still failing
+        LastFailed::record(&state_dir, "pre-commit", &[result("lint", true)]).unwrap();
+        assert_eq!(LastFailed::load(&state_dir, "pre-commit"), vec!["test"]);
+
+        LastFailed::record(&state_dir, "pre-commit", &[result("types", false)]).unwrap();
+        assert_eq!(
+            LastFailed::load(&state_dir, "pre-commit"),
+            vec!["test", "types"]
+        );
+    }
+}
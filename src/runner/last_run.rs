@@ -0,0 +1,103 @@
+//! Last successful run tracking for incremental (`--since-last-run`) runs
+
+use crate::utils::ensure_dir;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// File inside the state directory recording the last run of each hook
+const LAST_RUN_FILE: &str = "last-run.json";
+
+/// Reads and writes the time each hook last ran successfully
+pub struct LastRun;
+
+impl LastRun {
+    /// Load the time the given hook last ran successfully
+    pub fn load(state_dir: &Path, hook_name: &str) -> Option<SystemTime> {
+        Self::read_all(state_dir)
+            .get(hook_name)
+            .map(|ms| UNIX_EPOCH + Duration::from_millis(*ms))
+    }
+
+    /// Record the time the given hook last ran successfully
+    pub fn record(state_dir: &Path, hook_name: &str, time: SystemTime) -> Result<()> {
+        let mut runs = Self::read_all(state_dir);
+        let ms = time
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        runs.insert(hook_name.to_string(), ms);
+
+        ensure_dir(state_dir)?;
+        let path = state_dir.join(LAST_RUN_FILE);
+        fs::write(&path, serde_json::to_string_pretty(&runs)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Keep only the files (relative to `root`) modified after `since`
+    pub fn modified_since(files: &[PathBuf], root: &Path, since: SystemTime) -> Vec<PathBuf> {
+        files
+            .iter()
+            .filter(|f| {
+                fs::metadata(root.join(f))
+                    .and_then(|m| m.modified())
+                    .map(|modified| modified > since)
+                    .unwrap_or(false)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Read all recorded runs, treating a missing or corrupt file as empty
+    fn read_all(state_dir: &Path) -> HashMap<String, u64> {
+        fs::read_to_string(state_dir.join(LAST_RUN_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread::sleep;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_and_load() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_dir = temp_dir.path().join("fasthooks");
+        assert!(LastRun::load(&state_dir, "pre-commit").is_none());
+
+        let now = SystemTime::now();
+        LastRun::record(&state_dir, "pre-commit", now).unwrap();
+
+        let loaded = LastRun::load(&state_dir, "pre-commit").unwrap();
+        assert!(now.duration_since(loaded).unwrap() < Duration::from_millis(1));
+        assert!(LastRun::load(&state_dir, "pre-push").is_none());
+    }
+
+    #[test]
+    fn test_second_run_only_sees_touched_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let files = vec![PathBuf::from("a.rs"), PathBuf::from("b.rs")];
+        for file in &files {
+            fs::write(root.join(file), "fn main() {}").unwrap();
+        }
+
+        // First run sees everything, then records its start time
+        sleep(Duration::from_millis(20));
+        let first_run = SystemTime::now();
+        LastRun::record(&root.join("state"), "pre-commit", first_run).unwrap();
+
+        sleep(Duration::from_millis(20));
+        fs::write(root.join("b.rs"), "fn main() { println!(); }").unwrap();
+
+        let since = LastRun::load(&root.join("state"), "pre-commit").unwrap();
+        let changed = LastRun::modified_since(&files, root, since);
+        assert_eq!(changed, vec![PathBuf::from("b.rs")]);
+    }
+}
@@ -0,0 +1,194 @@
+//! Prometheus text-format metrics for the node_exporter textfile collector
+
+use super::HookResult;
+use crate::utils::write_atomic;
+use anyhow::Result;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Metric families written for every hook run: name and help text (all gauges)
+const FAMILIES: &[(&str, &str)] = &[
+    (
+        "fasthooks_hook_duration_ms",
+        "Wall time of the last run of the hook in milliseconds",
+    ),
+    (
+        "fasthooks_hook_success",
+        "Whether the last run of the hook passed (1) or failed (0)",
+    ),
+    (
+        "fasthooks_hook_last_run_timestamp_seconds",
+        "Unix time at which the hook last finished",
+    ),
+    (
+        "fasthooks_task_duration_ms",
+        "Duration of the task in the last run of its hook in milliseconds",
+    ),
+    (
+        "fasthooks_task_success",
+        "Whether the task passed (1) or failed (0) in the last run of its hook",
+    ),
+];
+
+/// Sample lines (`name{labels} value`) describing one hook run
+pub fn samples(hook_name: &str, result: &HookResult, finished_at: SystemTime) -> Vec<String> {
+    let hook = format!("hook=\"{}\"", escape(hook_name));
+    let timestamp = finished_at
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    let mut lines = vec![
+        format!(
+            "fasthooks_hook_duration_ms{{{}}} {}",
+            hook, result.stats.wall_time_ms
+        ),
+        format!(
+            "fasthooks_hook_success{{{}}} {}",
+            hook,
+            u8::from(result.success)
+        ),
+        format!(
+            "fasthooks_hook_last_run_timestamp_seconds{{{}}} {}",
+            hook, timestamp
+        ),
+    ];
+
+    for task in &result.tasks {
+        let labels = format!("{},task=\"{}\"", hook, escape(&task.name));
+        lines.push(format!(
+            "fasthooks_task_duration_ms{{{}}} {}",
+            labels, task.duration_ms
+        ));
+        lines.push(format!(
+            "fasthooks_task_success{{{}}} {}",
+            labels,
+            u8::from(task.success)
+        ));
+    }
+
+    lines
+}
+
+/// Render sample lines grouped under the `# HELP` / `# TYPE` header of their family
+pub fn render(samples: &[String]) -> String {
+    let mut output = String::new();
+
+    for (family, help) in FAMILIES {
+        let members: Vec<&String> = samples
+            .iter()
+            .filter(|line| metric_name(line) == *family)
+            .collect();
+        if members.is_empty() {
+            continue;
+        }
+
+        output.push_str(&format!("# HELP {} {}\n", family, help));
+        output.push_str(&format!("# TYPE {} gauge\n", family));
+        for line in members {
+            output.push_str(line);
+            output.push('\n');
+        }
+    }
+
+    output
+}
+
+/// Update `path` with the metrics of this run, keeping samples of other hooks.
+///
+/// The file is replaced atomically so the collector never reads a partial write.
+pub fn write_metrics(path: &Path, hook_name: &str, result: &HookResult) -> Result<()> {
+    let own_label = format!("hook=\"{}\"", escape(hook_name));
+    let existing = std::fs::read_to_string(path).unwrap_or_default();
+
+    let mut lines: Vec<String> = existing
+        .lines()
+        .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+        .filter(|line| !line.contains(&own_label))
+        .map(str::to_string)
+        .collect();
+    lines.extend(samples(hook_name, result, SystemTime::now()));
+
+    write_atomic(path, render(&lines))
+}
+
+/// Name of the metric a sample line belongs to
+fn metric_name(line: &str) -> &str {
+    line.split(['{', ' ']).next().unwrap_or_default()
+}
+
+/// Escape a label value (backslash, double quote and newline)
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::TaskResult;
+    use tempfile::TempDir;
+
+    fn hook_result() -> HookResult {
+        HookResult::new(
+            vec![
+                TaskResult::success("fmt".to_string(), String::new(), String::new(), 120),
+                TaskResult::failure(
+                    "lint \"strict\"".to_string(),
+                    1,
+                    String::new(),
+                    String::new(),
+                    300,
+                ),
+            ],
+            350,
+        )
+    }
+
+    #[test]
+    fn test_metrics_file_format_and_merge() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("fasthooks.prom");
+
+        write_metrics(&path, "pre-push", &hook_result()).unwrap();
+        write_metrics(&path, "pre-commit", &hook_result()).unwrap();
+        write_metrics(&path, "pre-commit", &hook_result()).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+
+        // Parse `name{labels} value` samples
+        let samples: Vec<(&str, &str, f64)> = content
+            .lines()
+            .filter(|l| !l.starts_with('#'))
+            .map(|l| {
+                let (series, value) = l.rsplit_once(' ').unwrap();
+                let (name, labels) = series.split_once('{').unwrap();
+                (name, labels.trim_end_matches('}'), value.parse().unwrap())
+            })
+            .collect();
+
+        assert!(samples.contains(&("fasthooks_hook_duration_ms", "hook=\"pre-commit\"", 350.0)));
+        assert!(samples.contains(&("fasthooks_hook_success", "hook=\"pre-push\"", 0.0)));
+        assert!(samples.contains(&(
+            "fasthooks_task_success",
+            "hook=\"pre-commit\",task=\"fmt\"",
+            1.0
+        )));
+        assert!(samples.contains(&(
+            "fasthooks_task_duration_ms",
+            "hook=\"pre-commit\",task=\"lint \\\"strict\\\"\"",
+            300.0
+        )));
+
+        // Re-running a hook replaces its samples; each family has one header
+        assert_eq!(samples.len(), 2 * 7);
+        assert_eq!(
+            content
+                .matches("# TYPE fasthooks_hook_success gauge")
+                .count(),
+            1
+        );
+        assert_eq!(content.matches("# HELP ").count(), FAMILIES.len());
+    }
+}
@@ -3,20 +3,38 @@
 //! Handles parallel execution of hook tasks with performance tracking.
 
 mod executor;
+pub mod github;
+mod index_checkout;
+pub mod json;
+pub mod junit;
+mod last_failed;
+mod last_run;
+pub mod metrics;
+mod preflight;
+pub mod report;
+pub mod shuffle;
+pub mod staged_checks;
 mod stats;
+mod timings;
+pub mod webhook;
 
 pub use executor::TaskExecutor;
+pub use index_checkout::IndexCheckout;
+pub use last_failed::LastFailed;
+pub use last_run::LastRun;
 pub use stats::ExecutionStats;
+pub use timings::{TaskTimings, Timings};
+
+use serde::Serialize;
 
 /// Result of a task execution
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct TaskResult {
     /// Task name
     pub name: String,
     /// Whether the task succeeded
     pub success: bool,
     /// Exit code (available for error handling)
-    #[allow(dead_code)]
     pub exit_code: i32,
     /// Standard output
     pub stdout: String,
@@ -24,6 +42,10 @@ pub struct TaskResult {
     pub stderr: String,
     /// Execution duration in milliseconds
     pub duration_ms: u64,
+    /// Fully substituted command that was executed
+    pub command: String,
+    /// Number of files passed to a globbed task
+    pub file_count: Option<usize>,
 }
 
 impl TaskResult {
@@ -36,6 +58,8 @@ impl TaskResult {
             stdout,
             stderr,
             duration_ms,
+            command: String::new(),
+            file_count: None,
         }
     }
 
@@ -54,22 +78,80 @@ impl TaskResult {
             stdout,
             stderr,
             duration_ms,
+            command: String::new(),
+            file_count: None,
+        }
+    }
+
+    /// Record the command that produced this result
+    pub fn with_command(mut self, command: String) -> Self {
+        self.command = command;
+        self
+    }
+
+    /// Drop the captured stdout and stderr
+    pub fn without_output(mut self) -> Self {
+        self.stdout.clear();
+        self.stderr.clear();
+        self
+    }
+
+    /// Record how many files the task processed
+    pub fn with_file_count(mut self, file_count: usize) -> Self {
+        self.file_count = Some(file_count);
+        self
+    }
+}
+
+/// Whether a task would run, and why
+#[derive(Debug, Clone)]
+pub struct TaskExplanation {
+    /// Whether the task would be executed
+    pub will_run: bool,
+    /// Human-readable reason for the decision
+    pub reason: String,
+}
+
+impl TaskExplanation {
+    /// Create an explanation for a task that would run
+    pub fn run(reason: String) -> Self {
+        Self {
+            will_run: true,
+            reason,
+        }
+    }
+
+    /// Create an explanation for a task that would be skipped
+    pub fn skip(reason: String) -> Self {
+        Self {
+            will_run: false,
+            reason,
         }
     }
 }
 
+/// A task that was not executed, and why
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedTask {
+    /// Task name
+    pub name: String,
+    /// Human-readable reason the task was skipped
+    pub reason: String,
+}
+
 /// Result of running all tasks in a hook
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct HookResult {
     /// Individual task results
     pub tasks: Vec<TaskResult>,
     /// Total execution time in milliseconds (used in stats)
-    #[allow(dead_code)]
     pub total_duration_ms: u64,
     /// Whether all tasks succeeded
     pub success: bool,
     /// Execution statistics
     pub stats: ExecutionStats,
+    /// Tasks that were skipped without running
+    pub skipped: Vec<SkippedTask>,
 }
 
 impl HookResult {
@@ -83,6 +165,13 @@ impl HookResult {
             total_duration_ms,
             success,
             stats,
+            skipped: Vec::new(),
         }
     }
+
+    /// Attach the tasks that were skipped during the run
+    pub fn with_skipped(mut self, skipped: Vec<SkippedTask>) -> Self {
+        self.skipped = skipped;
+        self
+    }
 }
@@ -0,0 +1,94 @@
+//! Pre-flight checks that task commands can be resolved before anything runs
+
+use std::path::Path;
+
+/// Shell builtins and keywords that never resolve on PATH
+const SHELL_BUILTINS: &[&str] = &[
+    ".", ":", "[", "alias", "break", "cd", "command", "continue", "echo", "eval", "exec", "exit",
+    "export", "false", "if", "printf", "pwd", "read", "return", "set", "shift", "source", "test",
+    "then", "trap", "true", "type", "ulimit", "umask", "unset", "wait", "while", "for", "do",
+    "done", "fi", "else", "case", "esac", "copy", "del", "dir", "md", "rd", "ren", "rem",
+];
+
+/// Get the program names invoked by a shell command line.
+///
+/// Splits on `&&`, `||`, `;` and `|`, then takes the first word of each
+/// segment after skipping leading `VAR=value` assignments.
+pub fn program_names(command: &str) -> Vec<&str> {
+    command
+        .split("&&")
+        .flat_map(|s| s.split("||"))
+        .flat_map(|s| s.split([';', '|']))
+        .filter_map(|segment| {
+            segment
+                .split_whitespace()
+                .find(|word| !is_env_assignment(word))
+        })
+        .map(|name| name.trim_matches(|c| matches!(c, '(' | ')' | '"' | '\'')))
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Check whether a program can be resolved from `cwd`
+pub fn is_resolvable(program: &str, cwd: &Path) -> bool {
+    if SHELL_BUILTINS.contains(&program) || program.starts_with('$') {
+        return true;
+    }
+
+    if program.contains('/') || program.contains('\\') {
+        return cwd.join(program).exists();
+    }
+
+    which::which(program).is_ok()
+}
+
+/// Get the programs in a command that can't be resolved from `cwd`
+pub fn missing_programs<'a>(command: &'a str, cwd: &Path) -> Vec<&'a str> {
+    program_names(command)
+        .into_iter()
+        .filter(|program| !is_resolvable(program, cwd))
+        .collect()
+}
+
+/// Check if a word is a `VAR=value` environment assignment
+fn is_env_assignment(word: &str) -> bool {
+    match word.split_once('=') {
+        Some((name, _)) => {
+            !name.is_empty()
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                && !name.starts_with(|c: char| c.is_ascii_digit())
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_program_names() {
+        assert_eq!(program_names("eslint --fix ."), vec!["eslint"]);
+        assert_eq!(
+            program_names("npm run build && cargo test; echo done | tee log"),
+            vec!["npm", "cargo", "echo", "tee"]
+        );
+        assert_eq!(program_names("NODE_ENV=test CI=1 jest"), vec!["jest"]);
+    }
+
+    #[test]
+    fn test_missing_programs() {
+        let cwd = Path::new(".");
+        assert!(missing_programs("cd src && echo ok", cwd).is_empty());
+        assert_eq!(
+            missing_programs("echo hi && fasthooks-missing-tool --fix", cwd),
+            vec!["fasthooks-missing-tool"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_missing_programs_resolves_path() {
+        assert!(missing_programs("sh -c true", Path::new(".")).is_empty());
+    }
+}
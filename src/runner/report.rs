@@ -0,0 +1,157 @@
+//! Helpers for rendering task output in hook reports
+
+use super::TaskResult;
+use crate::config::Hook;
+use colored::Color;
+use std::collections::HashMap;
+
+/// Colors for task names; red and green are left out since they mark failure and success
+const TASK_PALETTE: &[Color] = &[
+    Color::Cyan,
+    Color::Magenta,
+    Color::Blue,
+    Color::Yellow,
+    Color::BrightCyan,
+    Color::BrightMagenta,
+    Color::BrightBlue,
+    Color::BrightYellow,
+];
+
+/// Task results grouped under an optional stage name
+pub type StageGroup<'a> = (Option<&'a str>, Vec<&'a TaskResult>);
+
+/// Group task results by their task's `stage`, in config order.
+///
+/// Stages appear in the order their first task is declared, and results within a
+/// stage follow the task order; tasks without a stage form an unnamed group.
+pub fn group_by_stage<'a>(hook: &'a Hook, results: &'a [TaskResult]) -> Vec<StageGroup<'a>> {
+    let mut groups: Vec<StageGroup<'a>> = Vec::new();
+
+    for task in &hook.tasks {
+        let Some(result) = results.iter().find(|r| r.name == task.name) else {
+            continue;
+        };
+        let stage = task.stage.as_deref();
+        match groups.iter_mut().find(|(s, _)| *s == stage) {
+            Some((_, members)) => members.push(result),
+            None => groups.push((stage, vec![result])),
+        }
+    }
+
+    groups
+}
+
+/// Collapse repeated identical lines, keeping first-occurrence order and
+/// annotating repeats with a count (e.g. `error: missing semicolon (×12)`).
+///
+/// Blank lines are kept as-is so paragraph spacing survives.
+pub fn dedupe_lines(output: &str) -> String {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for line in output.lines().filter(|l| !l.trim().is_empty()) {
+        *counts.entry(line).or_insert(0) += 1;
+    }
+
+    let mut emitted = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    for line in output.lines() {
+        if line.trim().is_empty() {
+            emitted.push(line.to_string());
+            continue;
+        }
+        if !seen.insert(line) {
+            continue;
+        }
+        match counts[line] {
+            1 => emitted.push(line.to_string()),
+            n => emitted.push(format!("{} (×{})", line, n)),
+        }
+    }
+
+    emitted.join("\n")
+}
+
+/// Stable color for a task name, so the same task looks the same in every run.
+///
+/// Uses FNV-1a rather than the std hasher, whose output may change between Rust releases.
+pub fn task_color(name: &str) -> Color {
+    let hash = name.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+    TASK_PALETTE[(hash % TASK_PALETTE.len() as u64) as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Task;
+
+    #[test]
+    fn test_task_color_is_deterministic() {
+        assert_eq!(task_color("lint"), task_color("lint"));
+        // Pinned so a change to the hash (and everyone's colors) is deliberate
+        assert_eq!(task_color("lint"), Color::BrightCyan);
+        assert_eq!(task_color("test"), Color::BrightMagenta);
+        assert!(TASK_PALETTE.contains(&task_color("")));
+
+        // Different names spread over the palette
+        let colors: Vec<Color> = ["lint", "test", "fmt", "build", "typecheck", "audit"]
+            .iter()
+            .map(|name| task_color(name))
+            .collect();
+        assert!(colors.iter().any(|c| *c != colors[0]));
+    }
+
+    #[test]
+    fn test_dedupe_lines_collapses_repeats() {
+        let output = "error: bad import\nwarning: unused\nerror: bad import\nerror: bad import";
+        assert_eq!(
+            dedupe_lines(output),
+            "error: bad import (×3)\nwarning: unused"
+        );
+    }
+
+    #[test]
+    fn test_dedupe_lines_keeps_unique_output() {
+        let output = "line one\n\nline two";
+        assert_eq!(dedupe_lines(output), output);
+    }
+
+    #[test]
+    fn test_group_by_stage_in_config_order() {
+        let task = |name: &str, stage: Option<&str>| Task {
+            name: name.to_string(),
+            run: "true".into(),
+            stage: stage.map(String::from),
+            ..Default::default()
+        };
+        let hook = Hook {
+            tasks: vec![
+                task("eslint", Some("Lint")),
+                task("unit", Some("Test")),
+                task("prettier", Some("Lint")),
+                task("audit", None),
+            ],
+            ..Default::default()
+        };
+        // Results arrive in completion order, not config order
+        let results: Vec<TaskResult> = ["audit", "prettier", "unit", "eslint"]
+            .iter()
+            .map(|name| TaskResult::success(name.to_string(), String::new(), String::new(), 1))
+            .collect();
+
+        let groups = group_by_stage(&hook, &results);
+        let names: Vec<(Option<&str>, Vec<&str>)> = groups
+            .iter()
+            .map(|(stage, members)| (*stage, members.iter().map(|r| r.name.as_str()).collect()))
+            .collect();
+
+        assert_eq!(
+            names,
+            vec![
+                (Some("Lint"), vec!["eslint", "prettier"]),
+                (Some("Test"), vec!["unit"]),
+                (None, vec!["audit"]),
+            ]
+        );
+    }
+}
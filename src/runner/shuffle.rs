@@ -0,0 +1,109 @@
+//! Seeded task shuffling for catching hidden ordering dependencies
+
+use crate::config::Task;
+use std::collections::HashSet;
+
+/// Small SplitMix64 generator: deterministic for a given seed, no dependencies
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform-enough index in `0..bound` (bound > 0)
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+}
+
+/// Pick a seed when `--shuffle` is given without one
+pub fn random_seed() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
+/// Shuffle the tasks that neither depend on nor are depended on by another task.
+///
+/// Only the slots held by those independent tasks are permuted, so dependency
+/// order stays valid for both the sequential and dependency-aware executors.
+pub fn shuffle_independent(tasks: &mut [&Task], seed: u64) {
+    let depended_on: HashSet<&str> = tasks
+        .iter()
+        .flat_map(|t| t.depends_on.iter().map(String::as_str))
+        .collect();
+
+    let slots: Vec<usize> = tasks
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| t.depends_on.is_empty() && !depended_on.contains(t.name.as_str()))
+        .map(|(i, _)| i)
+        .collect();
+
+    // Fisher-Yates over the independent slots
+    let mut rng = SplitMix64(seed);
+    for i in (1..slots.len()).rev() {
+        let j = rng.below(i + 1);
+        tasks.swap(slots[i], slots[j]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(name: &str, depends_on: &[&str]) -> Task {
+        Task {
+            name: name.to_string(),
+            run: "true".into(),
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn order(tasks: &[Task], seed: u64) -> Vec<String> {
+        let mut refs: Vec<&Task> = tasks.iter().collect();
+        shuffle_independent(&mut refs, seed);
+        refs.iter().map(|t| t.name.clone()).collect()
+    }
+
+    #[test]
+    fn test_shuffle_is_deterministic_for_seed() {
+        let tasks: Vec<Task> = ["a", "b", "c", "d", "e", "f"]
+            .iter()
+            .map(|n| task(n, &[]))
+            .collect();
+
+        let first = order(&tasks, 42);
+        assert_eq!(first, order(&tasks, 42));
+
+        let mut sorted = first.clone();
+        sorted.sort();
+        assert_eq!(sorted, vec!["a", "b", "c", "d", "e", "f"]);
+
+        // Some seed produces a different order
+        assert!((0..10).any(|seed| order(&tasks, seed) != first));
+    }
+
+    #[test]
+    fn test_shuffle_keeps_dependency_chain_in_place() {
+        let tasks = vec![
+            task("build", &[]),
+            task("lint", &[]),
+            task("test", &["build"]),
+            task("fmt", &[]),
+        ];
+
+        for seed in 0..20 {
+            let shuffled = order(&tasks, seed);
+            assert_eq!(shuffled[0], "build");
+            assert_eq!(shuffled[2], "test");
+        }
+    }
+}
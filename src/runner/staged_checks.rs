@@ -0,0 +1,132 @@
+//! Built-in pre-commit checks on staged file contents, run before any task
+
+use crate::config::Settings;
+use crate::hooks::GitRepository;
+use anyhow::Result;
+
+/// Run the enabled checks against the staged files, failing with every offending file
+pub fn check(repo: &GitRepository, settings: &Settings) -> Result<()> {
+    if !settings.check_conflict_markers && settings.max_file_size_bytes.is_none() {
+        return Ok(());
+    }
+
+    let mut problems = Vec::new();
+    for path in repo.staged_files()? {
+        if let Some(limit) = settings.max_file_size_bytes {
+            if let Some(size) = repo.staged_size(&path)?.filter(|&size| size > limit) {
+                problems.push(format!(
+                    "{} is {} bytes, over the {} byte limit (consider Git LFS: git lfs track \"{}\")",
+                    path.display(),
+                    size,
+                    limit,
+                    path.display()
+                ));
+            }
+        }
+
+        if settings.check_conflict_markers {
+            let Some(content) = repo.staged_content(&path)? else {
+                continue;
+            };
+            if has_conflict_markers(&content) {
+                problems.push(format!("conflict markers in {}", path.display()));
+            }
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        anyhow::bail!(
+            "Staged files failed built-in checks:\n  {}",
+            problems.join("\n  ")
+        )
+    }
+}
+
+/// Whether content contains a `<<<<<<<` or `>>>>>>>` conflict marker line.
+///
+/// `=======` alone is not enough since it's also a Markdown heading underline.
+/// Binary content (containing NUL bytes) is never flagged.
+pub fn has_conflict_markers(content: &[u8]) -> bool {
+    if content.contains(&0) {
+        return false;
+    }
+
+    content.split(|&b| b == b'\n').any(|line| {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        [b"<<<<<<<", b">>>>>>>"].iter().any(|marker| {
+            line.strip_prefix(*marker)
+                .is_some_and(|rest| rest.is_empty() || rest[0] == b' ')
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Repository;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_has_conflict_markers() {
+        let conflict = b"fn a() {}\n<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> feature\n";
+        assert!(has_conflict_markers(conflict));
+        assert!(!has_conflict_markers(b"Title\n=======\n\nText\n"));
+        assert!(!has_conflict_markers(b"<<<<<<<<<< not a marker\n"));
+        assert!(!has_conflict_markers(b"\0<<<<<<< HEAD\n"));
+    }
+
+    #[test]
+    fn test_check_reports_staged_conflicts() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let raw = Repository::init(root).unwrap();
+
+        std::fs::write(root.join("clean.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(
+            root.join("merged.rs"),
+            "<<<<<<< HEAD\nfn a() {}\n=======\nfn b() {}\n>>>>>>> main\n",
+        )
+        .unwrap();
+        let mut index = raw.index().unwrap();
+        index.add_path(Path::new("clean.rs")).unwrap();
+        index.add_path(Path::new("merged.rs")).unwrap();
+        index.write().unwrap();
+
+        let repo = GitRepository::open(root).unwrap();
+        let mut settings = Settings::default();
+        assert!(check(&repo, &settings).is_ok());
+
+        settings.check_conflict_markers = true;
+        let error = check(&repo, &settings).unwrap_err().to_string();
+        assert!(error.contains("conflict markers in merged.rs"));
+        assert!(!error.contains("clean.rs"));
+    }
+
+    #[test]
+    fn test_check_reports_oversized_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let raw = Repository::init(root).unwrap();
+
+        std::fs::write(root.join("small.txt"), "ok\n").unwrap();
+        std::fs::write(root.join("model.bin"), vec![7u8; 4096]).unwrap();
+        let mut index = raw.index().unwrap();
+        index.add_path(Path::new("small.txt")).unwrap();
+        index.add_path(Path::new("model.bin")).unwrap();
+        index.write().unwrap();
+
+        let repo = GitRepository::open(root).unwrap();
+        let settings = Settings {
+            max_file_size_bytes: Some(1024),
+            ..Default::default()
+        };
+
+        let error = check(&repo, &settings).unwrap_err().to_string();
+        assert!(error.contains("model.bin is 4096 bytes, over the 1024 byte limit"));
+        assert!(error.contains("git lfs track"));
+        assert!(!error.contains("small.txt"));
+    }
+}
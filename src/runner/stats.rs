@@ -2,9 +2,10 @@
 
 use super::TaskResult;
 use colored::Colorize;
+use serde::Serialize;
 
 /// Execution statistics for a hook run
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ExecutionStats {
     /// Total tasks executed
     pub total_tasks: usize,
@@ -21,6 +22,8 @@ pub struct ExecutionStats {
     pub parallel_savings_ms: u64,
     /// Estimated carbon savings
     pub carbon_savings: CarbonSavings,
+    /// Time for the whole command, including config loading and git discovery
+    pub command_time_ms: Option<u64>,
 }
 
 impl ExecutionStats {
@@ -43,9 +46,16 @@ impl ExecutionStats {
             cpu_time_ms,
             parallel_savings_ms,
             carbon_savings,
+            command_time_ms: None,
         }
     }
 
+    /// Record the total command time, which can't be less than the task wall time
+    pub fn with_command_time(mut self, command_time_ms: u64) -> Self {
+        self.command_time_ms = Some(command_time_ms.max(self.wall_time_ms));
+        self
+    }
+
     /// Format stats for display
     pub fn format(&self, show_carbon: bool) -> String {
         let mut output = String::new();
@@ -65,11 +75,16 @@ impl ExecutionStats {
 
         output.push_str(&format!("\n{}\n", status));
 
-        // Timing
+        // Timing: task wall time, then the whole command including setup
+        let total = self
+            .command_time_ms
+            .map(|ms| format!(" ({} total)", Self::format_duration(ms)))
+            .unwrap_or_default();
         output.push_str(&format!(
-            "  {} Completed in {}\n",
+            "  {} Completed in {}{}\n",
             "⏱".cyan(),
-            Self::format_duration(self.wall_time_ms)
+            Self::format_duration(self.wall_time_ms),
+            total.dimmed()
         ));
 
         // Parallel savings
@@ -94,7 +109,7 @@ impl ExecutionStats {
     }
 
     /// Format duration in human-readable format
-    fn format_duration(ms: u64) -> String {
+    pub fn format_duration(ms: u64) -> String {
         if ms < 1000 {
             format!("{}ms", ms)
         } else if ms < 60000 {
@@ -108,15 +123,13 @@ impl ExecutionStats {
 }
 
 /// Carbon savings estimate
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct CarbonSavings {
     /// Estimated grams of CO2 saved
     pub grams_co2: f64,
     /// Baseline comparison (Node.js estimated time)
-    #[allow(dead_code)]
     pub baseline_ms: u64,
     /// Actual execution time
-    #[allow(dead_code)]
     pub actual_ms: u64,
 }
 
@@ -135,22 +148,53 @@ impl CarbonSavings {
     pub fn calculate(actual_ms: u64) -> Self {
         // Estimate what Node.js would have taken
         let baseline_ms = (actual_ms as f64 * Self::NODEJS_OVERHEAD_FACTOR) as u64;
-        let time_saved_ms = baseline_ms.saturating_sub(actual_ms);
 
-        // Convert to hours
-        let time_saved_hours = time_saved_ms as f64 / 3_600_000.0;
-
-        // Calculate energy saved (kWh)
-        let energy_saved_kwh = (Self::CPU_POWER_WATTS * time_saved_hours) / 1000.0;
+        let mut savings = Self {
+            grams_co2: 0.0,
+            baseline_ms,
+            actual_ms,
+        };
 
         // Calculate CO2 saved (grams)
-        let grams_co2 = energy_saved_kwh * Self::CARBON_INTENSITY;
+        savings.grams_co2 = savings.energy_kwh() * Self::CARBON_INTENSITY;
+        savings
+    }
 
-        Self {
-            grams_co2,
-            baseline_ms,
-            actual_ms,
-        }
+    /// Energy saved in kWh, derived from the time saved vs the baseline
+    pub fn energy_kwh(&self) -> f64 {
+        let time_saved_ms = self.baseline_ms.saturating_sub(self.actual_ms);
+        let time_saved_hours = time_saved_ms as f64 / 3_600_000.0;
+        (Self::CPU_POWER_WATTS * time_saved_hours) / 1000.0
+    }
+
+    /// Describe the inputs and formula behind the estimate
+    pub fn explain(&self) -> String {
+        let mut output = String::new();
+        output.push_str("Carbon estimate breakdown:\n");
+        output.push_str(&format!("  Actual time:       {}ms\n", self.actual_ms));
+        output.push_str(&format!(
+            "  Baseline estimate: {}ms ({}x Node.js overhead)\n",
+            self.baseline_ms,
+            Self::NODEJS_OVERHEAD_FACTOR
+        ));
+        output.push_str(&format!(
+            "  CPU power:         {} W\n",
+            Self::CPU_POWER_WATTS
+        ));
+        output.push_str(&format!(
+            "  Carbon intensity:  {} gCO₂/kWh\n",
+            Self::CARBON_INTENSITY
+        ));
+        output.push_str(&format!(
+            "  Energy saved:      {:.9} kWh\n",
+            self.energy_kwh()
+        ));
+        output.push_str("  Formula: CO₂ (g) = CPU W × (baseline - actual) h / 1000 × intensity\n");
+        output.push_str(&format!(
+            "  Result:            {:.4}g CO₂\n",
+            self.grams_co2
+        ));
+        output
     }
 
     /// Calculate cumulative savings (for monthly/yearly reports)
@@ -180,6 +224,14 @@ mod tests {
         assert_eq!(savings.actual_ms, 100);
     }
 
+    #[test]
+    fn test_carbon_explain_lists_inputs() {
+        let explanation = CarbonSavings::calculate(1000).explain();
+        assert!(explanation.contains("475 gCO₂/kWh"));
+        assert!(explanation.contains("Baseline estimate: 5000ms"));
+        assert!(explanation.contains("65 W"));
+    }
+
     #[test]
     fn test_format_duration() {
         assert_eq!(ExecutionStats::format_duration(500), "500ms");
@@ -200,4 +252,17 @@ mod tests {
         assert_eq!(stats.cpu_time_ms, 300);
         assert_eq!(stats.parallel_savings_ms, 150);
     }
+
+    #[test]
+    fn test_format_reports_task_and_command_time() {
+        let stats = ExecutionStats::from_tasks(&[], 150).with_command_time(180);
+        assert_eq!(stats.command_time_ms, Some(180));
+        let formatted = stats.format(false);
+        assert!(formatted.contains("Completed in 150ms"));
+        assert!(formatted.contains("(180ms total)"));
+
+        // Total time never reads lower than the time spent in tasks
+        let stats = ExecutionStats::from_tasks(&[], 150).with_command_time(100);
+        assert_eq!(stats.command_time_ms, Some(150));
+    }
 }
@@ -0,0 +1,207 @@
+//! Task duration history for spotting hooks that get slower over time
+
+use super::{ExecutionStats, TaskResult};
+use crate::utils::ensure_dir;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// File inside the state directory recording recent durations of each task
+const TIMINGS_FILE: &str = "timings.json";
+
+/// Number of recent durations kept per task
+const MAX_SAMPLES: usize = 20;
+
+/// Runs needed before a task's median is trusted
+const MIN_SAMPLES: usize = 3;
+
+/// A task must take at least this long to be reported, so jitter in fast tasks is ignored
+const MIN_REPORTED_MS: u64 = 100;
+
+/// How much slower than its median a task must be to be reported (1.5 = 50% slower)
+const SLOWDOWN_FACTOR: f64 = 1.5;
+
+/// Recent durations in milliseconds, by task name
+pub type TaskTimings = HashMap<String, Vec<u64>>;
+
+/// A task that ran significantly slower than its historical median
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Slowdown {
+    /// Task name
+    pub task: String,
+    /// Duration of this run in milliseconds
+    pub duration_ms: u64,
+    /// Median of the recorded durations in milliseconds
+    pub median_ms: u64,
+}
+
+impl Slowdown {
+    /// Human-readable warning, e.g. "Task 'test' took 3.00s, 100% slower than its median of 1.50s"
+    pub fn message(&self) -> String {
+        let percent = (self.duration_ms as f64 / self.median_ms as f64 - 1.0) * 100.0;
+        format!(
+            "Task '{}' took {}, {:.0}% slower than its median of {}",
+            self.task,
+            ExecutionStats::format_duration(self.duration_ms),
+            percent,
+            ExecutionStats::format_duration(self.median_ms)
+        )
+    }
+}
+
+/// Reads and writes the recent durations of each hook's tasks
+pub struct Timings;
+
+impl Timings {
+    /// Load the recorded durations of the hook's tasks
+    pub fn load(state_dir: &Path, hook_name: &str) -> TaskTimings {
+        Self::read_all(state_dir)
+            .remove(hook_name)
+            .unwrap_or_default()
+    }
+
+    /// Add the durations of this run's successful tasks, keeping the most recent samples
+    pub fn record(state_dir: &Path, hook_name: &str, tasks: &[TaskResult]) -> Result<()> {
+        let mut all = Self::read_all(state_dir);
+        let timings = all.entry(hook_name.to_string()).or_default();
+        for task in tasks.iter().filter(|t| t.success) {
+            let samples = timings.entry(task.name.clone()).or_default();
+            samples.push(task.duration_ms);
+            if samples.len() > MAX_SAMPLES {
+                samples.drain(..samples.len() - MAX_SAMPLES);
+            }
+        }
+
+        ensure_dir(state_dir)?;
+        let path = state_dir.join(TIMINGS_FILE);
+        fs::write(&path, serde_json::to_string_pretty(&all)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Median recorded duration of a task, if it has any history
+    pub fn median_ms(timings: &TaskTimings, task_name: &str) -> Option<u64> {
+        median(timings.get(task_name)?)
+    }
+
+    /// Successful tasks of this run that were much slower than their recorded median
+    pub fn slowdowns(timings: &TaskTimings, tasks: &[TaskResult]) -> Vec<Slowdown> {
+        tasks
+            .iter()
+            .filter(|t| t.success && t.duration_ms >= MIN_REPORTED_MS)
+            .filter_map(|t| {
+                let samples = timings.get(&t.name)?;
+                if samples.len() < MIN_SAMPLES {
+                    return None;
+                }
+                let median_ms = Self::median_ms(timings, &t.name)?;
+                let slower = t.duration_ms as f64 > median_ms as f64 * SLOWDOWN_FACTOR;
+                slower.then(|| Slowdown {
+                    task: t.name.clone(),
+                    duration_ms: t.duration_ms,
+                    median_ms,
+                })
+            })
+            .collect()
+    }
+
+    /// Read all recorded timings, treating a missing or corrupt file as empty
+    fn read_all(state_dir: &Path) -> HashMap<String, TaskTimings> {
+        fs::read_to_string(state_dir.join(TIMINGS_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Median of the samples (the mean of the two middle values for an even count)
+pub fn median(samples: &[u64]) -> Option<u64> {
+    let mut sorted = samples.to_vec();
+    sorted.sort_unstable();
+    let mid = sorted.len() / 2;
+    match sorted.len() {
+        0 => None,
+        len if len % 2 == 0 => Some((sorted[mid - 1] + sorted[mid]) / 2),
+        _ => Some(sorted[mid]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn ok(name: &str, duration_ms: u64) -> TaskResult {
+        TaskResult::success(name.to_string(), String::new(), String::new(), duration_ms)
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(&[]), None);
+        assert_eq!(median(&[300, 100, 200]), Some(200));
+        assert_eq!(median(&[100, 400, 200, 300]), Some(250));
+    }
+
+    #[test]
+    fn test_slowdown_triggers_regression_warning() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_dir = temp_dir.path().join("fasthooks");
+        for duration in [1000, 1100, 900, 1000] {
+            Timings::record(
+                &state_dir,
+                "pre-commit",
+                &[ok("test", duration), ok("lint", 200)],
+            )
+            .unwrap();
+        }
+
+        let timings = Timings::load(&state_dir, "pre-commit");
+        assert_eq!(timings["test"], vec![1000, 1100, 900, 1000]);
+
+        // Within 50% of the median: no warning
+        assert!(Timings::slowdowns(&timings, &[ok("test", 1400), ok("lint", 250)]).is_empty());
+
+        let slowdowns = Timings::slowdowns(&timings, &[ok("test", 2000), ok("lint", 210)]);
+        assert_eq!(
+            slowdowns,
+            vec![Slowdown {
+                task: "test".to_string(),
+                duration_ms: 2000,
+                median_ms: 1000,
+            }]
+        );
+        assert_eq!(
+            slowdowns[0].message(),
+            "Task 'test' took 2.00s, 100% slower than its median of 1.00s"
+        );
+    }
+
+    #[test]
+    fn test_slowdowns_need_history_and_ignore_failures() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_dir = temp_dir.path().join("fasthooks");
+        Timings::record(&state_dir, "pre-commit", &[ok("test", 100)]).unwrap();
+        Timings::record(&state_dir, "pre-commit", &[ok("test", 100)]).unwrap();
+        let failed = TaskResult::failure("test".to_string(), 1, String::new(), String::new(), 5);
+        Timings::record(&state_dir, "pre-commit", &[failed]).unwrap();
+
+        // Failed runs aren't recorded, so two samples aren't enough to compare against
+        let timings = Timings::load(&state_dir, "pre-commit");
+        assert_eq!(timings["test"].len(), 2);
+        assert!(Timings::slowdowns(&timings, &[ok("test", 5000)]).is_empty());
+        assert!(Timings::load(&state_dir, "pre-push").is_empty());
+    }
+
+    #[test]
+    fn test_record_keeps_recent_samples() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_dir = temp_dir.path().join("fasthooks");
+        for duration in 0..(MAX_SAMPLES as u64 + 5) {
+            Timings::record(&state_dir, "pre-commit", &[ok("test", duration)]).unwrap();
+        }
+
+        let samples = &Timings::load(&state_dir, "pre-commit")["test"];
+        assert_eq!(samples.len(), MAX_SAMPLES);
+        assert_eq!(samples[0], 5);
+    }
+}
@@ -0,0 +1,117 @@
+//! POST hook results as JSON to `[settings.webhook]` for centralized monitoring
+
+use super::{json, HookResult};
+use crate::config::{NotifyOn, WebhookSettings};
+use anyhow::{Context, Result};
+use std::time::Duration;
+
+/// Post the result if the webhook's `on` policy wants it
+pub fn post(settings: &WebhookSettings, hook_name: &str, result: &HookResult) -> Result<()> {
+    let wanted = match settings.on {
+        NotifyOn::Always => true,
+        NotifyOn::Failure => !result.success,
+        NotifyOn::Success => result.success,
+    };
+    if !wanted {
+        return Ok(());
+    }
+
+    let agent = ureq::AgentBuilder::new()
+        .timeout(Duration::from_millis(settings.timeout_ms))
+        .build();
+    agent
+        .post(&settings.url)
+        .set("Content-Type", "application/json")
+        .send_string(&json::to_json(hook_name, result)?)
+        .with_context(|| format!("Failed to post results to webhook {}", settings.url))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::{ExecutionStats, TaskResult};
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    /// Accept one request and return its body
+    fn receive_one(listener: TcpListener) -> String {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line == "\r\n" {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        reader
+            .get_mut()
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+            .unwrap();
+        String::from_utf8(body).unwrap()
+    }
+
+    #[test]
+    fn test_post_sends_hook_result() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || receive_one(listener));
+
+        let tasks = vec![TaskResult::failure(
+            "lint".to_string(),
+            2,
+            String::new(),
+            "bad".to_string(),
+            40,
+        )];
+        let result = HookResult {
+            stats: ExecutionStats::from_tasks(&tasks, 45),
+            tasks,
+            total_duration_ms: 45,
+            success: false,
+            skipped: Vec::new(),
+        };
+        let settings = WebhookSettings {
+            url,
+            on: NotifyOn::Failure,
+            timeout_ms: 5000,
+        };
+        post(&settings, "pre-commit", &result).unwrap();
+
+        let body: serde_json::Value = serde_json::from_str(&server.join().unwrap()).unwrap();
+        assert_eq!(body["hook"], "pre-commit");
+        assert_eq!(body["success"], false);
+        assert_eq!(body["total_duration_ms"], 45);
+        assert_eq!(body["tasks"][0]["name"], "lint");
+        assert_eq!(body["tasks"][0]["exit_code"], 2);
+        assert_eq!(body["stats"]["failed_tasks"], 1);
+    }
+
+    #[test]
+    fn test_post_respects_on_policy() {
+        let result = HookResult {
+            tasks: Vec::new(),
+            total_duration_ms: 0,
+            success: true,
+            stats: ExecutionStats::from_tasks(&[], 0),
+            skipped: Vec::new(),
+        };
+        // Nothing listens here; a success with `on = "failure"` must not connect at all
+        let settings = WebhookSettings {
+            url: "http://127.0.0.1:9/unreachable".to_string(),
+            on: NotifyOn::Failure,
+            timeout_ms: 100,
+        };
+        assert!(post(&settings, "pre-commit", &result).is_ok());
+    }
+}
@@ -23,6 +23,77 @@ pub fn is_ci() -> bool {
     CI_VARS.iter().any(|var| env::var(var).is_ok())
 }
 
+/// Check if the process runs with root privileges (effective uid 0)
+#[cfg(unix)]
+pub fn is_root() -> bool {
+    extern "C" {
+        fn geteuid() -> u32;
+    }
+    // SAFETY: geteuid has no preconditions and cannot fail
+    unsafe { geteuid() == 0 }
+}
+
+/// Check if the process runs with root privileges (never on non-Unix platforms)
+#[cfg(not(unix))]
+pub fn is_root() -> bool {
+    false
+}
+
+/// Get the current user's home directory
+pub fn home_dir() -> Option<String> {
+    env::var("HOME")
+        .or_else(|_| env::var("USERPROFILE"))
+        .ok()
+        .filter(|home| !home.is_empty())
+}
+
+/// Expand a leading `~` and `$VAR`/`${VAR}` references in a string.
+///
+/// Unset variables expand to an empty string, matching shell behavior.
+/// A `$` not followed by a valid variable name is kept literally.
+pub fn expand_vars(input: &str) -> String {
+    let input = match (input.strip_prefix('~'), home_dir()) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with(['/', '\\']) => {
+            format!("{}{}", home, rest)
+        }
+        _ => input.to_string(),
+    };
+
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            output.push(c);
+            continue;
+        }
+
+        if chars.peek() == Some(&'{') {
+            chars.next();
+            let name: String = chars.by_ref().take_while(|&c| c != '}').collect();
+            output.push_str(&env::var(&name).unwrap_or_default());
+            continue;
+        }
+
+        match chars.peek() {
+            Some(&c) if c.is_ascii_alphabetic() || c == '_' => {
+                let mut name = String::new();
+                while let Some(&c) = chars.peek() {
+                    if !c.is_ascii_alphanumeric() && c != '_' {
+                        break;
+                    }
+                    name.push(c);
+                    chars.next();
+                }
+                output.push_str(&env::var(&name).unwrap_or_default());
+            }
+            _ => output.push('$'),
+        }
+    }
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -33,4 +104,23 @@ mod tests {
         // Just verify the function doesn't panic
         let _ = is_ci();
     }
+
+    #[test]
+    fn test_expand_vars_env() {
+        env::set_var("FASTHOOKS_TEST_EXPAND", "project");
+        assert_eq!(expand_vars("$FASTHOOKS_TEST_EXPAND/src"), "project/src");
+        assert_eq!(expand_vars("${FASTHOOKS_TEST_EXPAND}-x"), "project-x");
+        assert_eq!(expand_vars("$FASTHOOKS_TEST_UNSET_VAR/src"), "/src");
+        assert_eq!(expand_vars("cost $5"), "cost $5");
+    }
+
+    #[test]
+    fn test_expand_vars_home() {
+        let Some(home) = home_dir() else {
+            return;
+        };
+        assert_eq!(expand_vars("~"), home);
+        assert_eq!(expand_vars("~/project"), format!("{}/project", home));
+        assert_eq!(expand_vars("~user/project"), "~user/project");
+    }
 }
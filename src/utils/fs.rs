@@ -3,9 +3,9 @@
 use anyhow::{Context, Result};
 use std::fs;
 use std::path::Path;
+use std::time::SystemTime;
 
 /// Ensure a directory exists, creating it if necessary
-#[allow(dead_code)]
 pub fn ensure_dir(path: &Path) -> Result<()> {
     if !path.exists() {
         fs::create_dir_all(path)
@@ -14,6 +14,44 @@ pub fn ensure_dir(path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Write a file atomically by writing a sibling temporary file and renaming it into place.
+///
+/// Readers (including a shell currently executing the file) see either the old or
+/// the new content, never a truncated file.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .with_context(|| format!("Invalid file path: {}", path.display()))?;
+    let temp_path = path.with_file_name(format!(".{}.tmp", file_name.to_string_lossy()));
+
+    fs::write(&temp_path, contents)
+        .with_context(|| format!("Failed to write {}", temp_path.display()))?;
+    fs::rename(&temp_path, path).with_context(|| format!("Failed to replace {}", path.display()))
+}
+
+/// Modification time of a file, or `None` if it doesn't exist
+pub fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Atomically write a file that was read earlier, refusing to clobber concurrent edits.
+///
+/// `read_at` is the file's modification time when it was read (`None` if it
+/// didn't exist yet); the write fails if the file has changed since.
+pub fn write_if_unchanged(
+    path: &Path,
+    contents: impl AsRef<[u8]>,
+    read_at: Option<SystemTime>,
+) -> Result<()> {
+    if modified_time(path) != read_at {
+        anyhow::bail!(
+            "{} was modified by another process since it was read; re-run the command",
+            path.display()
+        );
+    }
+    write_atomic(path, contents)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -28,4 +66,36 @@ mod tests {
         ensure_dir(&new_dir).unwrap();
         assert!(new_dir.exists());
     }
+
+    #[test]
+    fn test_write_atomic_replaces_content() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("hook");
+
+        write_atomic(&path, "first").unwrap();
+        write_atomic(&path, "second").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        assert_eq!(fs::read_dir(temp.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_write_if_unchanged_detects_stale_write() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("fasthooks.toml");
+
+        // Creating a new file
+        write_if_unchanged(&path, "first", None).unwrap();
+        let read_at = modified_time(&path);
+        assert!(write_if_unchanged(&path, "again", None).is_err());
+
+        // Someone else edits the file after we read it
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        fs::write(&path, "concurrent").unwrap();
+        assert!(write_if_unchanged(&path, "ours", read_at).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "concurrent");
+
+        write_if_unchanged(&path, "ours", modified_time(&path)).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "ours");
+    }
 }
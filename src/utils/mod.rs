@@ -3,8 +3,8 @@
 mod env;
 mod fs;
 
+pub use env::{expand_vars, is_root};
 // Re-export for potential future use
 #[allow(unused_imports)]
 pub use env::is_ci;
-#[allow(unused_imports)]
-pub use fs::ensure_dir;
+pub use fs::{ensure_dir, modified_time, write_atomic, write_if_unchanged};
@@ -29,3 +29,14 @@ fn test_list_no_config() {
         .assert()
         .success();
 }
+
+#[test]
+fn test_no_subcommand_shows_status() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let mut cmd = Command::cargo_bin("fasthooks").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("FastHooks Status"))
+        .stdout(predicate::str::contains("No fasthooks.toml found"));
+}
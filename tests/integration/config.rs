@@ -1,8 +1,5 @@
 //! Configuration integration tests
 
-use std::fs;
-use tempfile::TempDir;
-
 #[test]
 fn test_parse_minimal_config() {
     let config = r#"
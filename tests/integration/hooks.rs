@@ -1,6 +1,5 @@
 //! Hook management integration tests
 
-use std::fs;
 use std::process::Command;
 use tempfile::TempDir;
 